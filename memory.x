@@ -2,6 +2,7 @@ MEMORY
 {
   /* NOTE 1 K = 1 KiBi = 1024 bytes */
   /* You must fill in these values for your application */
-  FLASH : ORIGIN = 0x00000000, LENGTH = 512K
+  /* The last two 4K pages are left to the high score table, see HIGHSCORE_FLASH in main.rs */
+  FLASH : ORIGIN = 0x00000000, LENGTH = 504K
   RAM : ORIGIN = 0x20000000, LENGTH = 128K
 }
@@ -0,0 +1,734 @@
+use heapless::Deque;
+
+use crate::{
+    clock::Clock,
+    controller::{BfsBot, Controller},
+    difficulty::{DifficultyProfile, PROFILES},
+    draw::Canvas,
+    game::{Game, GameConfig, MoveResult, TailRule},
+    hotseat::HotSeat,
+    input::ButtonCode,
+    level::Campaign,
+    menu::{Action, GameMode, Menu, Settings, MAIN_PAGE},
+    replay::Recording,
+    snapshot::{CellState, Snapshot},
+    versus::{Versus, VersusResult},
+};
+
+// inputs kept for the replay of a game, a game with more of them can't be replayed
+pub const REPLAY_INPUTS: usize = 256;
+
+// how long the menu waits for a press before the demo starts
+pub const DEMO_IDLE_MS: u64 = 30_000;
+pub const DEMO_TURN_DELAY_MS: u64 = 250;
+pub const LEVEL_NUMBER_MS: u64 = 1000;
+// how long the end of a game waits for a restart before it goes back to the menu
+pub const GAME_OVER_MS: u64 = 10_000;
+
+// turns kept for the coming ticks of a game, each tick takes exactly one; a turn
+// pressed while this many wait is dropped
+const PENDING_INPUTS: usize = 2;
+
+type Board = Game<5, 5, 25>;
+type VersusBoard = Versus<5, 5, 25>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    Menu,
+    /// Editing a setting from the menu.
+    Settings,
+    /// The bot plays while nobody touches the menu.
+    Demo,
+    /// Shows the number of the coming level.
+    Intro,
+    /// Shows the number of the hot-seat player whose turn it is, until they press.
+    Turn(usize),
+    /// A game of the mode picked in the menu is running.
+    Playing,
+    Paused,
+    /// The game, or the campaign, ended with this result.
+    GameOver(MoveResult),
+    /// A versus round ended with this result.
+    VersusOver(VersusResult),
+    /// Every hot-seat player has played, shows the number of the winner.
+    Ranking,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    Input(ButtonCode),
+    /// The timer of the current state ran out.
+    Timer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transition {
+    pub from: State,
+    pub to: State,
+}
+
+/// The device from the menu to the end of a game, driven by presses and a clock.
+///
+/// In the menu a long idle time starts the demo, any press ends it. An endless game
+/// and a versus round start right away, a campaign shows the number of each level
+/// before it is played and a hot-seat game the number of each player. A+B pauses and
+/// resumes a game and a long A in the pause gives up, except in versus where both
+/// buttons steer. At the end B plays the same mode again, A or waiting goes back to
+/// the menu.
+///
+/// The timer of a state starts with the first `poll` or `next_deadline_ms` in it, so
+/// whatever the caller plays on the way in doesn't eat into it.
+pub struct App<C> {
+    clock: C,
+    state: State,
+    menu: Menu,
+    // the length of the timer that starts with the next poll
+    timer_ms: Option<u64>,
+    deadline_ms: Option<u64>,
+    campaign: Campaign,
+    score: u32,
+    game: Option<Board>,
+    inputs: Deque<ButtonCode, PENDING_INPUTS>,
+    recording: Recording<REPLAY_INPUTS>,
+    tick: u32,
+    versus: Option<VersusBoard>,
+    hot_seat: HotSeat,
+    demo: Option<Board>,
+    // the long press of the button that ended the demo, still held on the way to the
+    // menu and not meant for it
+    held: Option<ButtonCode>,
+}
+
+impl<C: Clock> App<C> {
+    pub fn new(clock: C, settings: Settings) -> Self {
+        App {
+            clock,
+            state: State::Menu,
+            menu: Menu::new(&MAIN_PAGE, settings),
+            timer_ms: Some(DEMO_IDLE_MS),
+            deadline_ms: None,
+            campaign: Campaign::default(),
+            score: 0,
+            game: None,
+            inputs: Deque::new(),
+            recording: Recording::new(0, 0, GameConfig::default()),
+            tick: 0,
+            versus: None,
+            hot_seat: HotSeat::new(settings.players),
+            demo: None,
+            held: None,
+        }
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    pub fn settings(&self) -> &Settings {
+        self.menu.settings()
+    }
+
+    pub fn profile(&self) -> &'static DifficultyProfile {
+        &PROFILES[self.settings().difficulty]
+    }
+
+    /// Score of the game, or of the campaign so far.
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    /// Position of the current level, counted from zero.
+    pub fn level_index(&self) -> usize {
+        self.campaign.get_index()
+    }
+
+    /// The current or the last game.
+    pub fn game(&self) -> Option<&Board> {
+        self.game.as_ref()
+    }
+
+    /// The inputs of the current or the last game.
+    pub fn recording(&self) -> &Recording<REPLAY_INPUTS> {
+        &self.recording
+    }
+
+    /// The current or the last versus round.
+    pub fn versus(&self) -> Option<&VersusBoard> {
+        self.versus.as_ref()
+    }
+
+    /// The players of the current or the last hot-seat game.
+    pub fn hot_seat(&self) -> &HotSeat {
+        &self.hot_seat
+    }
+
+    /// When the timer of the current state runs out, `None` if it has none. A timer
+    /// still waiting for its first poll starts here.
+    pub fn next_deadline_ms(&mut self) -> Option<u64> {
+        if let Some(timer_ms) = self.timer_ms.take() {
+            self.deadline_ms = Some(self.clock.now_ms() + timer_ms);
+        }
+        self.deadline_ms
+    }
+
+    /// Handles `input` if there is one, the timer otherwise.
+    pub fn poll(&mut self, input: Option<ButtonCode>) -> Option<Transition> {
+        self.next_deadline_ms();
+        if let Some(input) = input {
+            self.handle(Event::Input(input))
+        } else if self
+            .deadline_ms
+            .is_some_and(|deadline| self.clock.now_ms() >= deadline)
+        {
+            self.handle(Event::Timer)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the transition `event` makes, if it leaves the current state.
+    pub fn handle(&mut self, event: Event) -> Option<Transition> {
+        // ticks are counted from when the last one was due, not from when it was
+        // handled, so a late poll doesn't put off the ones after it
+        let due_ms = match event {
+            Event::Timer => self.deadline_ms.take(),
+            Event::Input(_) => None,
+        };
+        let versus = self.settings().mode == GameMode::Versus;
+        let hot_seat = self.settings().mode == GameMode::HotSeat;
+        let from = self.state;
+        let to = match (from, event) {
+            (State::Menu | State::Settings, Event::Input(input)) => {
+                if self.held.take() == Some(input) {
+                    from
+                } else {
+                    self.on_menu_input(input)
+                }
+            }
+            (State::Menu, Event::Timer) => {
+                self.start_demo();
+                State::Demo
+            }
+            // the press that ends the demo is used up by it
+            (State::Demo, Event::Input(input)) => {
+                self.held = match input {
+                    ButtonCode::PressedA | ButtonCode::DoublePressedA => {
+                        Some(ButtonCode::LongPressedA)
+                    }
+                    ButtonCode::PressedB | ButtonCode::DoublePressedB => {
+                        Some(ButtonCode::LongPressedB)
+                    }
+                    _ => None,
+                };
+                State::Menu
+            }
+            (State::Demo, Event::Timer) => self.demo_tick(due_ms),
+            (State::Intro, Event::Timer) => {
+                self.start_level();
+                State::Playing
+            }
+            (State::Turn(_), Event::Input(_)) => {
+                self.score = 0;
+                self.start_level();
+                State::Playing
+            }
+            (State::Playing, Event::Input(input)) if versus => {
+                // both snakes keep their own turns
+                if let Some(versus) = self.versus.as_mut() {
+                    versus.steer(input);
+                }
+                State::Playing
+            }
+            (State::Playing, Event::Input(ButtonCode::PressedAB)) => State::Paused,
+            // only turns take the slot of a tick, a long press in between doesn't
+            (
+                State::Playing,
+                Event::Input(
+                    input @ (ButtonCode::PressedA
+                    | ButtonCode::PressedB
+                    | ButtonCode::DoublePressedA
+                    | ButtonCode::DoublePressedB),
+                ),
+            ) => {
+                let _ = self.inputs.push_back(input);
+                State::Playing
+            }
+            (State::Playing, Event::Timer) => self.game_tick(due_ms),
+            (State::Paused, Event::Input(ButtonCode::PressedAB)) => State::Playing,
+            (State::Paused, Event::Input(ButtonCode::LongPressedA)) => State::Menu,
+            // whatever ends the game of a hot-seat player hands the board on
+            (State::GameOver(_), _) if hot_seat => match self.hot_seat.current_player() {
+                Some(player) => State::Turn(player),
+                None => State::Ranking,
+            },
+            (
+                State::GameOver(_) | State::VersusOver(_) | State::Ranking,
+                Event::Input(ButtonCode::PressedB),
+            ) => self.start(),
+            (State::GameOver(_) | State::VersusOver(_) | State::Ranking, _) => State::Menu,
+            _ => from,
+        };
+        self.enter(from, to)
+    }
+
+    pub fn get_snapshot(&self) -> Snapshot<5, 5> {
+        let versus = self.settings().mode == GameMode::Versus;
+        match self.state {
+            State::Menu | State::Settings => self.menu.get_snapshot(),
+            State::Demo => self
+                .demo
+                .as_ref()
+                .map(Game::get_snapshot)
+                .unwrap_or_default(),
+            State::Intro => digit(self.campaign.get_index() + 1),
+            State::Turn(player) => digit(player),
+            State::Playing if versus => self
+                .versus
+                .as_ref()
+                .map(Versus::get_snapshot)
+                .unwrap_or_default(),
+            State::Playing => self
+                .game
+                .as_ref()
+                .map(Game::get_snapshot)
+                .unwrap_or_default(),
+            State::Paused | State::GameOver(_) => self
+                .game
+                .as_ref()
+                .map(|game| game.get_snapshot().blinking())
+                .unwrap_or_default(),
+            State::VersusOver(_) => self
+                .versus
+                .as_ref()
+                .map(|versus| versus.get_snapshot().blinking())
+                .unwrap_or_default(),
+            State::Ranking => self
+                .hot_seat
+                .ranking()
+                .first()
+                .map(|winner| digit(winner.player))
+                .unwrap_or_default(),
+        }
+    }
+
+    fn enter(&mut self, from: State, to: State) -> Option<Transition> {
+        if to == from {
+            return None;
+        }
+        if to == State::Menu && from != State::Settings && from != State::Demo {
+            self.menu.home();
+        }
+        self.state = to;
+        self.arm(match to {
+            State::Menu => Some(DEMO_IDLE_MS),
+            State::Demo => Some(DEMO_TURN_DELAY_MS),
+            State::Intro => Some(LEVEL_NUMBER_MS),
+            State::Playing => Some(self.turn_delay_ms()),
+            // the number of the next player follows whatever the caller plays
+            State::GameOver(_) if self.settings().mode == GameMode::HotSeat => Some(0),
+            State::GameOver(_) | State::VersusOver(_) | State::Ranking => Some(GAME_OVER_MS),
+            State::Settings | State::Turn(_) | State::Paused => None,
+        });
+        Some(Transition { from, to })
+    }
+
+    /// Starts a timer of `timer_ms` with the next poll, `None` stops the timer.
+    fn arm(&mut self, timer_ms: Option<u64>) {
+        self.timer_ms = timer_ms;
+        self.deadline_ms = None;
+    }
+
+    /// Sets the next tick `period_ms` after the one that was due at `due_ms`.
+    fn tick_again(&mut self, due_ms: Option<u64>, period_ms: u64) {
+        match due_ms {
+            Some(due_ms) => {
+                self.timer_ms = None;
+                self.deadline_ms = Some(due_ms + period_ms);
+            }
+            None => self.arm(Some(period_ms)),
+        }
+    }
+
+    fn on_menu_input(&mut self, input: ButtonCode) -> State {
+        match self.menu.is_action_picked(input) {
+            Some(Action::Play) => self.start(),
+            None if self.menu.is_editing() => State::Settings,
+            None => {
+                // the demo waits for the menu to sit idle
+                self.arm(Some(DEMO_IDLE_MS));
+                State::Menu
+            }
+        }
+    }
+
+    fn start_demo(&mut self) {
+        let config = GameConfig {
+            tail: TailRule::Chase,
+            ..Default::default()
+        };
+        self.demo = Some(Game::with_seed(self.clock.now_ms() as u32, config));
+    }
+
+    fn demo_tick(&mut self, due_ms: Option<u64>) -> State {
+        if let Some(game) = self.demo.as_mut() {
+            if game.step(BfsBot.next_input(game)) != MoveResult::Trivial {
+                self.start_demo();
+            }
+        }
+        self.tick_again(due_ms, DEMO_TURN_DELAY_MS);
+        State::Demo
+    }
+
+    /// Starts the mode picked in the menu from scratch, returns the state it starts in.
+    fn start(&mut self) -> State {
+        self.campaign = Campaign::default();
+        self.score = 0;
+        self.game = None;
+        let settings = *self.settings();
+        match settings.mode {
+            GameMode::Endless => {
+                self.start_level();
+                State::Playing
+            }
+            GameMode::Campaign => State::Intro,
+            GameMode::Versus => {
+                let seed = self.clock.now_ms() as u32;
+                let tail = self.profile().tail;
+                self.versus = Some(Versus::with_seed(seed, settings.boundary, tail));
+                State::Playing
+            }
+            GameMode::HotSeat => {
+                self.hot_seat = HotSeat::new(settings.players);
+                State::Turn(1)
+            }
+        }
+    }
+
+    fn start_level(&mut self) {
+        let profile = self.profile();
+        let settings = self.settings();
+        let config = GameConfig {
+            boundary: settings.boundary,
+            score_multiplier: profile.score_multiplier,
+            food: profile.food,
+            tail: profile.tail,
+            level: (settings.mode == GameMode::Campaign).then(|| self.campaign.level()),
+        };
+        let seed = self.clock.now_ms() as u32;
+        self.recording = Recording::new(seed, settings.difficulty as u8, config);
+        self.game = Some(Game::with_seed(seed, config));
+        self.inputs.clear();
+        self.tick = 0;
+    }
+
+    /// Moves the game of any mode on by one tick.
+    fn game_tick(&mut self, due_ms: Option<u64>) -> State {
+        let state = if self.settings().mode == GameMode::Versus {
+            self.versus_step()
+        } else {
+            self.step()
+        };
+        if state == State::Playing {
+            self.tick += 1;
+            self.tick_again(due_ms, self.turn_delay_ms());
+        }
+        state
+    }
+
+    fn versus_step(&mut self) -> State {
+        let Some(versus) = self.versus.as_mut() else {
+            return State::Menu;
+        };
+        match versus.step() {
+            VersusResult::Running => State::Playing,
+            result => State::VersusOver(result),
+        }
+    }
+
+    fn step(&mut self) -> State {
+        let mode = self.settings().mode;
+        let Some(game) = self.game.as_mut() else {
+            return State::Menu;
+        };
+        let input = self.inputs.pop_front();
+        if let Some(code) = input {
+            // a lost input only costs the replay
+            let _ = self.recording.record(self.tick, code);
+        }
+        let result = game.step(input);
+        if result == MoveResult::Trivial {
+            return State::Playing;
+        }
+        self.recording.finish(self.tick, result, game.len());
+        self.score += game.score();
+        match mode {
+            GameMode::Campaign if result == MoveResult::LevelCleared => {
+                if self.campaign.advance() {
+                    State::Intro
+                } else {
+                    State::GameOver(result)
+                }
+            }
+            GameMode::HotSeat => {
+                self.hot_seat.record(game);
+                State::GameOver(result)
+            }
+            _ => State::GameOver(result),
+        }
+    }
+
+    fn turn_delay_ms(&self) -> u64 {
+        let speed = self.profile().speed;
+        match self.settings().mode {
+            // the snakes of a versus round don't speed up
+            GameMode::Versus => speed.start_ms,
+            _ => speed.turn_delay_ms(self.game.as_ref().map_or(1, Game::len)),
+        }
+    }
+}
+
+/// A number like that of a level or a player, for a moment.
+fn digit(number: usize) -> Snapshot<5, 5> {
+    Canvas::new(CellState::AnimationStatic(1000))
+        .glyph(char::from_digit(number as u32, 10).unwrap_or('?'))
+        .finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{boundary::BoundaryMode, clock::FakeClock, game::Direction, versus::Player};
+
+    use ButtonCode::{LongPressedA, PressedA, PressedAB, PressedB};
+
+    fn app(clock: &FakeClock) -> App<&FakeClock> {
+        app_in(clock, GameMode::Endless)
+    }
+
+    fn app_in(clock: &FakeClock, mode: GameMode) -> App<&FakeClock> {
+        let settings = Settings {
+            mode,
+            boundary: BoundaryMode::Walls,
+            ..Default::default()
+        };
+        App::new(clock, settings)
+    }
+
+    /// Polls every 10 ms for `ms`, returns the states it went through.
+    fn run(app: &mut App<&FakeClock>, clock: &FakeClock, ms: u64) -> std::vec::Vec<State> {
+        let mut states = std::vec::Vec::new();
+        for _ in 0..ms / 10 {
+            if let Some(transition) = app.poll(None) {
+                states.push(transition.to);
+            }
+            clock.advance(10);
+        }
+        states
+    }
+
+    fn transition(app: &mut App<&FakeClock>, input: ButtonCode) -> Option<State> {
+        app.poll(Some(input)).map(|transition| transition.to)
+    }
+
+    #[test]
+    fn menu_starts_the_demo_when_idle() {
+        let clock = FakeClock::new();
+        let mut app = app(&clock);
+        assert!(run(&mut app, &clock, DEMO_IDLE_MS - 10).is_empty());
+        // a press starts the wait over
+        assert_eq!(transition(&mut app, PressedA), None);
+        assert!(run(&mut app, &clock, DEMO_IDLE_MS - 10).is_empty());
+        assert_eq!(run(&mut app, &clock, 20), [State::Demo]);
+
+        let before = app.get_snapshot().buffer;
+        run(&mut app, &clock, DEMO_TURN_DELAY_MS * 3);
+        assert_ne!(app.get_snapshot().buffer, before);
+        assert_eq!(transition(&mut app, PressedB), Some(State::Menu));
+        assert_eq!(app.settings().mode, GameMode::Endless);
+    }
+
+    #[test]
+    fn button_held_after_the_demo_is_not_a_long_press_in_the_menu() {
+        let clock = FakeClock::new();
+        let mut app = app(&clock);
+        for _ in 0..5 {
+            assert_eq!(transition(&mut app, PressedA), None);
+        }
+        assert_eq!(transition(&mut app, PressedB), None);
+        let options = app.get_snapshot().buffer;
+        assert_eq!(run(&mut app, &clock, DEMO_IDLE_MS + 10), [State::Demo]);
+        assert_eq!(transition(&mut app, PressedA), Some(State::Menu));
+        // A is still held and its long press follows
+        assert_eq!(transition(&mut app, LongPressedA), None);
+        assert_eq!(app.get_snapshot().buffer, options);
+        // the next long press is meant for the menu again
+        assert_eq!(transition(&mut app, LongPressedA), None);
+        assert_ne!(app.get_snapshot().buffer, options);
+
+        // a new press means the button was let go in between
+        assert_eq!(run(&mut app, &clock, DEMO_IDLE_MS + 10), [State::Demo]);
+        assert_eq!(transition(&mut app, PressedB), Some(State::Menu));
+        let before = app.get_snapshot().buffer;
+        assert_eq!(transition(&mut app, PressedA), None);
+        assert_ne!(app.get_snapshot().buffer, before);
+    }
+
+    #[test]
+    fn editing_a_setting_is_its_own_state() {
+        let clock = FakeClock::new();
+        let mut app = app(&clock);
+        assert_eq!(transition(&mut app, PressedA), None);
+        assert_eq!(transition(&mut app, PressedB), Some(State::Settings));
+        // no demo while a setting is edited
+        assert!(run(&mut app, &clock, DEMO_IDLE_MS * 2).is_empty());
+        assert_eq!(transition(&mut app, PressedA), None);
+        assert_eq!(transition(&mut app, PressedA), None);
+        assert_eq!(transition(&mut app, PressedB), Some(State::Menu));
+        assert_eq!(app.settings().mode, GameMode::Versus);
+    }
+
+    #[test]
+    fn endless_game_starts_right_away_and_over_again() {
+        let clock = FakeClock::new();
+        let mut app = app(&clock);
+        assert_eq!(transition(&mut app, PressedB), Some(State::Playing));
+        assert_eq!(app.recording().config.level, None);
+        let delay = app.profile().speed.start_ms;
+        let states = run(&mut app, &clock, delay * 4);
+        assert_eq!(states, [State::GameOver(MoveResult::HitWall)]);
+
+        assert_eq!(transition(&mut app, PressedB), Some(State::Playing));
+        assert_eq!(app.game().unwrap().head().row, 2);
+        assert_eq!(app.score(), 0);
+    }
+
+    #[test]
+    fn campaign_runs_from_intro_to_game_over() {
+        let clock = FakeClock::new();
+        let mut app = app_in(&clock, GameMode::Campaign);
+        assert_eq!(transition(&mut app, PressedB), Some(State::Intro));
+        assert_eq!(
+            run(&mut app, &clock, LEVEL_NUMBER_MS + 10),
+            [State::Playing]
+        );
+        // straight north into the wall, at the slowest speed of the profile
+        let delay = app.profile().speed.start_ms;
+        let states = run(&mut app, &clock, delay * 4);
+        assert_eq!(states, [State::GameOver(MoveResult::HitWall)]);
+        assert_eq!(app.game().unwrap().head().row, 0);
+        assert!(app.recording().inputs().is_empty());
+
+        // B plays the campaign again, waiting goes back to the menu
+        assert_eq!(transition(&mut app, PressedB), Some(State::Intro));
+        run(&mut app, &clock, LEVEL_NUMBER_MS + 10);
+        run(&mut app, &clock, delay * 4);
+        assert_eq!(run(&mut app, &clock, GAME_OVER_MS + 10), [State::Menu]);
+    }
+
+    #[test]
+    fn late_polls_do_not_put_ticks_off() {
+        let clock = FakeClock::new();
+        let mut app = app(&clock);
+        transition(&mut app, PressedB);
+        let first = app.next_deadline_ms().unwrap();
+        clock.advance(first - clock.now_ms() + 70);
+        assert_eq!(app.poll(None), None);
+        let length = app.game().unwrap().len();
+        let second = first + app.profile().speed.turn_delay_ms(length);
+        assert_eq!(app.next_deadline_ms(), Some(second));
+        assert_eq!(app.game().unwrap().head().row, 1);
+    }
+
+    #[test]
+    fn long_presses_do_not_take_the_place_of_turns() {
+        let clock = FakeClock::new();
+        let mut app = app(&clock);
+        transition(&mut app, PressedB);
+        for input in [PressedA, LongPressedA, PressedA] {
+            assert_eq!(transition(&mut app, input), None);
+        }
+        let delay = app.profile().speed.start_ms;
+        run(&mut app, &clock, delay * 2 + 10);
+        assert_eq!(app.recording().inputs(), [(0, PressedA), (1, PressedA)]);
+        assert_eq!(app.game().unwrap().direction(), Direction::South);
+    }
+
+    #[test]
+    fn pause_stops_the_clock_of_the_game() {
+        let clock = FakeClock::new();
+        let mut app = app(&clock);
+        transition(&mut app, PressedB);
+        assert_eq!(transition(&mut app, PressedAB), Some(State::Paused));
+        let head = app.game().unwrap().head();
+        assert!(run(&mut app, &clock, 60_000).is_empty());
+        assert_eq!(app.game().unwrap().head(), head);
+        assert_eq!(
+            app.get_snapshot().buffer,
+            app.game().unwrap().get_snapshot().blinking().buffer
+        );
+
+        assert_eq!(transition(&mut app, PressedAB), Some(State::Playing));
+        // turns are recorded at the tick they are taken
+        assert_eq!(transition(&mut app, PressedA), None);
+        let delay = app.profile().speed.start_ms;
+        run(&mut app, &clock, delay + 10);
+        assert_eq!(app.recording().inputs(), [(0, PressedA)]);
+        assert_eq!(transition(&mut app, PressedAB), Some(State::Paused));
+        assert_eq!(transition(&mut app, LongPressedA), Some(State::Menu));
+    }
+
+    #[test]
+    fn versus_round_runs_to_the_end() {
+        let clock = FakeClock::new();
+        let mut app = app_in(&clock, GameMode::Versus);
+        assert_eq!(transition(&mut app, PressedB), Some(State::Playing));
+        // both buttons at once steer both snakes, there is no pause
+        assert_eq!(transition(&mut app, PressedAB), None);
+        let delay = app.profile().speed.start_ms;
+        run(&mut app, &clock, delay + 10);
+        let versus = app.versus().unwrap();
+        assert_eq!(versus.direction(Player::One), Direction::West);
+        assert_eq!(versus.direction(Player::Two), Direction::Ost);
+
+        // both run into the walls on the same tick
+        let states = run(&mut app, &clock, delay * 4);
+        assert_eq!(states, [State::VersusOver(VersusResult::Draw)]);
+        assert_eq!(transition(&mut app, PressedB), Some(State::Playing));
+        assert_eq!(app.versus().unwrap().len(Player::One), 1);
+        run(&mut app, &clock, delay * 4);
+        assert_eq!(run(&mut app, &clock, GAME_OVER_MS + 10), [State::Menu]);
+    }
+
+    #[test]
+    fn hot_seat_players_take_turns_until_the_ranking() {
+        let clock = FakeClock::new();
+        let mut app = app_in(&clock, GameMode::HotSeat);
+        assert_eq!(transition(&mut app, PressedB), Some(State::Turn(1)));
+        // the board waits for the player to take it
+        assert!(run(&mut app, &clock, 60_000).is_empty());
+        assert_eq!(transition(&mut app, PressedA), Some(State::Playing));
+        assert_eq!(app.recording().config.level, None);
+        let delay = app.profile().speed.start_ms;
+        assert_eq!(
+            run(&mut app, &clock, delay * 4),
+            [State::GameOver(MoveResult::HitWall), State::Turn(2)]
+        );
+
+        // a game pauses and quits like any other
+        assert_eq!(transition(&mut app, PressedB), Some(State::Playing));
+        assert_eq!(transition(&mut app, PressedAB), Some(State::Paused));
+        assert!(run(&mut app, &clock, 60_000).is_empty());
+        assert_eq!(transition(&mut app, PressedAB), Some(State::Playing));
+        assert_eq!(
+            run(&mut app, &clock, delay * 4),
+            [State::GameOver(MoveResult::HitWall), State::Ranking]
+        );
+        assert_eq!(app.hot_seat().results().len(), 2);
+        assert_eq!(app.get_snapshot(), digit(1));
+
+        assert_eq!(transition(&mut app, PressedB), Some(State::Turn(1)));
+        assert_eq!(transition(&mut app, PressedB), Some(State::Playing));
+        assert_eq!(transition(&mut app, PressedAB), Some(State::Paused));
+        assert_eq!(transition(&mut app, LongPressedA), Some(State::Menu));
+    }
+}
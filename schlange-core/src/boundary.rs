@@ -0,0 +1,257 @@
+use crate::{
+    draw::Canvas,
+    game::{Coordinate, Direction},
+    input::ButtonCode,
+    snapshot::{CellState, Snapshot},
+};
+
+/// A pair of edge cells: leaving the board through one of them brings the head
+/// back in through the other one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Portal {
+    pub a: Coordinate,
+    pub b: Coordinate,
+}
+
+impl Portal {
+    pub fn partner(&self, coordinate: Coordinate) -> Option<Coordinate> {
+        if coordinate == self.a {
+            Some(self.b)
+        } else if coordinate == self.b {
+            Some(self.a)
+        } else {
+            None
+        }
+    }
+}
+
+/// What happens when the head crosses the edge of the board.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundaryMode {
+    /// The board is a torus, the head reappears on the opposite edge.
+    #[default]
+    Wrap,
+    /// The edge is solid, crossing it ends the game.
+    Walls,
+    /// The edge is solid except for the given portals.
+    Portals(&'static [Portal]),
+}
+
+impl BoundaryMode {
+    /// The cell next to `head` in `direction` and the heading there, crossing the
+    /// edge of the board if needed, `None` if it crashed into the edge.
+    pub fn advance<const NCOLS: usize, const NROWS: usize>(
+        &self,
+        head: Coordinate,
+        direction: Direction,
+    ) -> Option<(Coordinate, Direction)> {
+        let on_board = match direction {
+            Direction::Ost => (head.col as usize + 1 < NCOLS).then(|| Coordinate {
+                row: head.row,
+                col: head.col + 1,
+            }),
+            Direction::South => (head.row as usize + 1 < NROWS).then(|| Coordinate {
+                col: head.col,
+                row: head.row + 1,
+            }),
+            Direction::West => (head.col > 0).then(|| Coordinate {
+                row: head.row,
+                col: head.col - 1,
+            }),
+            Direction::North => (head.row > 0).then(|| Coordinate {
+                col: head.col,
+                row: head.row - 1,
+            }),
+        };
+        match on_board {
+            Some(coordinate) => Some((coordinate, direction)),
+            None => self.cross::<NCOLS, NROWS>(head, direction),
+        }
+    }
+
+    /// Where the head ends up and where it is heading after leaving the board
+    /// from `head` in `direction`, `None` if it crashed into the edge.
+    pub fn cross<const NCOLS: usize, const NROWS: usize>(
+        &self,
+        head: Coordinate,
+        direction: Direction,
+    ) -> Option<(Coordinate, Direction)> {
+        match self {
+            BoundaryMode::Wrap => {
+                let (col, row) = (head.col as usize, head.row as usize);
+                let wrapped = match direction {
+                    Direction::Ost => Coordinate {
+                        row: head.row,
+                        col: ((col + 1) % NCOLS) as u8,
+                    },
+                    Direction::South => Coordinate {
+                        col: head.col,
+                        row: ((row + 1) % NROWS) as u8,
+                    },
+                    Direction::West => Coordinate {
+                        row: head.row,
+                        col: ((col + NCOLS - 1) % NCOLS) as u8,
+                    },
+                    Direction::North => Coordinate {
+                        col: head.col,
+                        row: ((row + NROWS - 1) % NROWS) as u8,
+                    },
+                };
+                Some((wrapped, direction))
+            }
+            BoundaryMode::Walls => None,
+            BoundaryMode::Portals(portals) => portals
+                .iter()
+                .find_map(|portal| portal.partner(head))
+                .map(|exit| (exit, inward::<NCOLS, NROWS>(exit, direction))),
+        }
+    }
+}
+
+/// The direction pointing into the board from the edge cell `exit`, corners keep
+/// `direction` if it already points inwards.
+fn inward<const NCOLS: usize, const NROWS: usize>(
+    exit: Coordinate,
+    direction: Direction,
+) -> Direction {
+    let candidates = [
+        (exit.row == 0, Direction::South),
+        (exit.row as usize == NROWS - 1, Direction::North),
+        (exit.col == 0, Direction::Ost),
+        (exit.col as usize == NCOLS - 1, Direction::West),
+    ];
+    candidates
+        .iter()
+        .find(|&&(on_edge, inward)| on_edge && inward == direction)
+        .or_else(|| candidates.iter().find(|&&(on_edge, _)| on_edge))
+        .map_or(direction, |&(_, inward)| inward)
+}
+
+/// Portals in the middle of opposite edges of the 5x5 board.
+pub const CROSS_PORTALS: [Portal; 2] = [
+    Portal {
+        a: Coordinate { row: 0, col: 2 },
+        b: Coordinate { row: 4, col: 2 },
+    },
+    Portal {
+        a: Coordinate { row: 2, col: 0 },
+        b: Coordinate { row: 2, col: 4 },
+    },
+];
+
+pub struct BoundarySelector {
+    boundary_mode: BoundaryMode,
+}
+
+impl BoundarySelector {
+    pub fn new() -> Self {
+        Self::with_boundary_mode(Default::default())
+    }
+
+    /// A selector that offers `boundary_mode` first.
+    pub fn with_boundary_mode(boundary_mode: BoundaryMode) -> Self {
+        BoundarySelector { boundary_mode }
+    }
+
+    pub fn is_choice_made(&mut self, input: ButtonCode) -> Option<()> {
+        match input {
+            ButtonCode::PressedA => {
+                self.rotate_boundary_mode();
+                None
+            }
+            ButtonCode::PressedB => Some(()),
+            _ => None,
+        }
+    }
+
+    fn rotate_boundary_mode(&mut self) {
+        self.boundary_mode = match self.boundary_mode {
+            BoundaryMode::Wrap => BoundaryMode::Walls,
+            BoundaryMode::Walls => BoundaryMode::Portals(&CROSS_PORTALS),
+            BoundaryMode::Portals(_) => BoundaryMode::Wrap,
+        };
+    }
+
+    pub fn get_boundary_mode(&self) -> BoundaryMode {
+        self.boundary_mode
+    }
+
+    pub fn get_snapshot(&self) -> Snapshot<5, 5> {
+        let icon: &[u8] = match self.boundary_mode {
+            // a cross reaching across all edges
+            BoundaryMode::Wrap => &[0b00100, 0b00100, 0b11111, 0b00100, 0b00100],
+            // a closed frame
+            BoundaryMode::Walls => &[0b11111, 0b10001, 0b10001, 0b10001, 0b11111],
+            // a frame with gaps in the middle of its sides
+            BoundaryMode::Portals(_) => &[0b11011, 0b10001, 0b00000, 0b10001, 0b11011],
+        };
+        Canvas::new(CellState::AnimationBlinking)
+            .centred_bitmap(icon)
+            .finish()
+    }
+}
+
+impl Default for BoundarySelector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CORNER_PORTAL: [Portal; 1] = [Portal {
+        a: Coordinate { row: 0, col: 3 },
+        b: Coordinate { row: 4, col: 0 },
+    }];
+
+    #[test]
+    fn wrap_moves_to_the_opposite_edge() {
+        let head = Coordinate { row: 0, col: 3 };
+        assert_eq!(
+            BoundaryMode::Wrap.cross::<5, 5>(head, Direction::North),
+            Some((Coordinate { row: 4, col: 3 }, Direction::North))
+        );
+    }
+
+    #[test]
+    fn walls_stop_the_head() {
+        let head = Coordinate { row: 0, col: 3 };
+        assert_eq!(
+            BoundaryMode::Walls.cross::<5, 5>(head, Direction::North),
+            None
+        );
+    }
+
+    #[test]
+    fn portals_lead_inwards_from_the_partner() {
+        let portals = BoundaryMode::Portals(&CORNER_PORTAL);
+        assert_eq!(
+            portals.cross::<5, 5>(Coordinate { row: 0, col: 3 }, Direction::North),
+            Some((Coordinate { row: 4, col: 0 }, Direction::North))
+        );
+        assert_eq!(
+            portals.cross::<5, 5>(Coordinate { row: 4, col: 0 }, Direction::West),
+            Some((Coordinate { row: 0, col: 3 }, Direction::South))
+        );
+        assert_eq!(
+            portals.cross::<5, 5>(Coordinate { row: 0, col: 2 }, Direction::North),
+            None
+        );
+    }
+
+    #[test]
+    fn a_rotates_through_all_modes() {
+        let mut selector = BoundarySelector::new();
+        assert_eq!(selector.get_boundary_mode(), BoundaryMode::Wrap);
+        selector.is_choice_made(ButtonCode::PressedA);
+        assert_eq!(selector.get_boundary_mode(), BoundaryMode::Walls);
+        selector.is_choice_made(ButtonCode::PressedA);
+        assert_eq!(
+            selector.get_boundary_mode(),
+            BoundaryMode::Portals(&CROSS_PORTALS)
+        );
+        assert!(selector.is_choice_made(ButtonCode::PressedB).is_some());
+    }
+}
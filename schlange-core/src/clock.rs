@@ -0,0 +1,35 @@
+use core::cell::Cell;
+
+/// Where the time comes from, so timed code can run against a fake clock on the host.
+pub trait Clock {
+    /// Milliseconds since some fixed point, never going backwards.
+    fn now_ms(&self) -> u64;
+}
+
+impl<C: Clock> Clock for &C {
+    fn now_ms(&self) -> u64 {
+        (**self).now_ms()
+    }
+}
+
+/// A clock that only moves when it is told to.
+#[derive(Debug, Default)]
+pub struct FakeClock {
+    now_ms: Cell<u64>,
+}
+
+impl FakeClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, ms: u64) {
+        self.now_ms.set(self.now_ms.get() + ms);
+    }
+}
+
+impl Clock for FakeClock {
+    fn now_ms(&self) -> u64 {
+        self.now_ms.get()
+    }
+}
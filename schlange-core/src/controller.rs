@@ -0,0 +1,311 @@
+use core::cmp::Reverse;
+
+use crate::{
+    boundary::BoundaryMode,
+    food::Board,
+    game::{Coordinate, Direction, Game},
+    input::ButtonCode,
+    rng::Rng,
+};
+
+/// Plays a game in place of the buttons.
+pub trait Controller<const NCOLS: usize, const NROWS: usize, const NCELLS: usize> {
+    /// The input for the next `Game::step`, `None` keeps the snake going straight on.
+    fn next_input<R: Rng>(&mut self, game: &Game<NCOLS, NROWS, NCELLS, R>) -> Option<ButtonCode>;
+}
+
+/// The button that turns a snake heading `from` towards `to`, `None` if it already heads
+/// there or if `to` is behind it, a snake can't turn around.
+pub fn turn(from: Direction, to: Direction) -> Option<ButtonCode> {
+    if to == from.left() {
+        Some(ButtonCode::PressedA)
+    } else if to == from.right() {
+        Some(ButtonCode::PressedB)
+    } else {
+        None
+    }
+}
+
+/// Takes the shortest way to the food as the snake lies now, or heads to where there
+/// is the most room if there is none.
+pub struct BfsBot;
+
+impl<const NCOLS: usize, const NROWS: usize, const NCELLS: usize> Controller<NCOLS, NROWS, NCELLS>
+    for BfsBot
+{
+    fn next_input<R: Rng>(&mut self, game: &Game<NCOLS, NROWS, NCELLS, R>) -> Option<ButtonCode> {
+        let board = game.board();
+        let heading = game.direction();
+        let options = [heading, heading.left(), heading.right()]
+            .into_iter()
+            .filter_map(|direction| {
+                let (cell, _) = board
+                    .boundary
+                    .advance::<NCOLS, NROWS>(board.head, direction)?;
+                board.is_free(cell).then_some((direction, cell))
+            });
+        let to_food = Board {
+            head: game.food(),
+            ..board
+        }
+        .distances();
+        // the first of equally good options wins, so the snake rather goes straight on
+        let closest = options
+            .clone()
+            .filter_map(|(direction, cell)| {
+                to_food[cell.col as usize][cell.row as usize].map(|distance| (direction, distance))
+            })
+            .min_by_key(|&(_, distance)| distance)
+            .map(|(direction, _)| direction);
+        let roomiest = || {
+            options
+                .clone()
+                .min_by_key(|&(_, cell)| {
+                    let room = Board {
+                        head: cell,
+                        ..board
+                    }
+                    .distances()
+                    .iter()
+                    .flatten()
+                    .filter(|distance| distance.is_some())
+                    .count();
+                    Reverse(room)
+                })
+                .map(|(direction, _)| direction)
+        };
+        closest
+            .or_else(roomiest)
+            .and_then(|direction| turn(heading, direction))
+    }
+}
+
+/// Follows a cycle through every cell of the board. That never goes wrong and wins
+/// every game, if slowly.
+pub struct HamiltonianBot<const NCOLS: usize, const NROWS: usize> {
+    // the way on from every cell
+    cycle: [[Direction; NROWS]; NCOLS],
+}
+
+impl<const NCOLS: usize, const NROWS: usize> HamiltonianBot<NCOLS, NROWS> {
+    /// A bot for `game`, which must not have started yet. `None` if there is no cycle
+    /// for its board: boards with walls in them and boards with an odd number of rows
+    /// and columns have none, unless the board wraps and the rows fit into the columns.
+    pub fn new<const NCELLS: usize, R: Rng>(game: &Game<NCOLS, NROWS, NCELLS, R>) -> Option<Self> {
+        let boundary = game.config().boundary;
+        if let Some(level) = game.config().level {
+            if cells::<NCOLS, NROWS>().any(|cell| level.is_wall(cell)) {
+                return None;
+            }
+        }
+        let mut cycle = [[Direction::North; NROWS]; NCOLS];
+        for cell in cells::<NCOLS, NROWS>() {
+            let (col, row) = (cell.col as usize, cell.row as usize);
+            cycle[col][row] = if NCOLS % 2 == 0 && NROWS >= 2 {
+                serpentine(col, row, NCOLS, NROWS)
+            } else if NROWS % 2 == 0 && NCOLS >= 2 {
+                transposed(serpentine(row, col, NROWS, NCOLS))
+            } else if boundary == BoundaryMode::Wrap && NCOLS % NROWS == 0 {
+                // north through every column, leaving it eastwards one row further down
+                // than it was entered
+                if row == col % NROWS {
+                    Direction::Ost
+                } else {
+                    Direction::North
+                }
+            } else {
+                return None;
+            };
+        }
+        let mut bot = HamiltonianBot { cycle };
+        let head = game.head();
+        if bot.way_on(head) == game.direction().opposite() {
+            bot.reverse(boundary);
+        }
+        Some(bot)
+    }
+
+    fn way_on(&self, cell: Coordinate) -> Direction {
+        self.cycle[cell.col as usize][cell.row as usize]
+    }
+
+    /// Runs the cycle the other way round.
+    fn reverse(&mut self, boundary: BoundaryMode) {
+        let mut reversed = self.cycle;
+        for cell in cells::<NCOLS, NROWS>() {
+            let direction = self.way_on(cell);
+            if let Some((next, _)) = boundary.advance::<NCOLS, NROWS>(cell, direction) {
+                reversed[next.col as usize][next.row as usize] = direction.opposite();
+            }
+        }
+        self.cycle = reversed;
+    }
+}
+
+impl<const NCOLS: usize, const NROWS: usize, const NCELLS: usize> Controller<NCOLS, NROWS, NCELLS>
+    for HamiltonianBot<NCOLS, NROWS>
+{
+    fn next_input<R: Rng>(&mut self, game: &Game<NCOLS, NROWS, NCELLS, R>) -> Option<ButtonCode> {
+        turn(game.direction(), self.way_on(game.head()))
+    }
+}
+
+fn cells<const NCOLS: usize, const NROWS: usize>() -> impl Iterator<Item = Coordinate> {
+    (0..NCOLS)
+        .flat_map(|col| (0..NROWS).map(move |row| (col, row)))
+        .map(|(col, row)| Coordinate {
+            row: row as u8,
+            col: col as u8,
+        })
+}
+
+/// The cycle of a board with an even number of columns: down and up the columns below
+/// the top row, then back along the top row.
+fn serpentine(col: usize, row: usize, ncols: usize, nrows: usize) -> Direction {
+    if row == 0 {
+        if col == 0 {
+            Direction::South
+        } else {
+            Direction::West
+        }
+    } else if col % 2 == 0 {
+        if row < nrows - 1 {
+            Direction::South
+        } else {
+            Direction::Ost
+        }
+    } else if row > 1 || col == ncols - 1 {
+        Direction::North
+    } else {
+        Direction::Ost
+    }
+}
+
+/// `direction` with rows and columns swapped.
+fn transposed(direction: Direction) -> Direction {
+    match direction {
+        Direction::North => Direction::West,
+        Direction::Ost => Direction::South,
+        Direction::South => Direction::Ost,
+        Direction::West => Direction::North,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game::{GameConfig, MoveResult, TailRule},
+        level::CAMPAIGN,
+    };
+
+    fn play<const NCOLS: usize, const NROWS: usize, const NCELLS: usize>(
+        game: &mut Game<NCOLS, NROWS, NCELLS>,
+        controller: &mut impl Controller<NCOLS, NROWS, NCELLS>,
+    ) -> MoveResult {
+        for _ in 0..100_000 {
+            let input = controller.next_input(game);
+            let result = game.step(input);
+            if result != MoveResult::Trivial {
+                return result;
+            }
+        }
+        panic!("the game did not end");
+    }
+
+    fn config(boundary: BoundaryMode) -> GameConfig {
+        GameConfig {
+            boundary,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn turns_are_relative() {
+        assert_eq!(
+            turn(Direction::North, Direction::West),
+            Some(ButtonCode::PressedA)
+        );
+        assert_eq!(
+            turn(Direction::West, Direction::North),
+            Some(ButtonCode::PressedB)
+        );
+        assert_eq!(turn(Direction::Ost, Direction::Ost), None);
+        assert_eq!(turn(Direction::South, Direction::North), None);
+    }
+
+    #[test]
+    fn hamiltonian_bot_wins_on_the_torus() {
+        for seed in 0..5 {
+            let mut game: Game<5, 5, 25> = Game::with_seed(seed, config(BoundaryMode::Wrap));
+            let mut bot = HamiltonianBot::new(&game).unwrap();
+            assert_eq!(play(&mut game, &mut bot), MoveResult::Win);
+            assert_eq!(game.len(), 25);
+        }
+    }
+
+    #[test]
+    fn hamiltonian_bot_wins_inside_walls() {
+        // the cycle runs south through the start, the bot turns it around
+        let mut game: Game<4, 6, 24> = Game::with_seed(3, config(BoundaryMode::Walls));
+        let mut bot = HamiltonianBot::new(&game).unwrap();
+        assert_eq!(play(&mut game, &mut bot), MoveResult::Win);
+
+        let mut game: Game<5, 4, 20> = Game::with_seed(3, config(BoundaryMode::Walls));
+        let mut bot = HamiltonianBot::new(&game).unwrap();
+        assert_eq!(play(&mut game, &mut bot), MoveResult::Win);
+    }
+
+    #[test]
+    fn hamiltonian_bot_needs_a_cycle() {
+        let game: Game<5, 5, 25> = Game::with_seed(1, config(BoundaryMode::Walls));
+        assert!(HamiltonianBot::new(&game).is_none());
+        let game: Game<5, 5, 25> = Game::with_seed(
+            1,
+            GameConfig {
+                level: Some(&CAMPAIGN[1]),
+                ..Default::default()
+            },
+        );
+        assert!(HamiltonianBot::new(&game).is_none());
+    }
+
+    #[test]
+    fn bfs_bot_takes_the_shortest_way() {
+        for seed in 0..20 {
+            let mut game: Game<5, 5, 25> = Game::with_seed(seed, config(BoundaryMode::Walls));
+            let (food, head) = (game.food(), game.head());
+            let mut shortest = food.row.abs_diff(head.row) + food.col.abs_diff(head.col);
+            if food.col == head.col && food.row > head.row {
+                // the snake has to turn around first
+                shortest += 2;
+            }
+            let mut steps = 0;
+            while game.food_eaten() == 0 {
+                let input = BfsBot.next_input(&game);
+                assert_eq!(game.step(input), MoveResult::Trivial);
+                steps += 1;
+            }
+            assert_eq!(steps, shortest, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn bfs_bot_gets_far() {
+        for seed in 0..5 {
+            let mut game: Game<5, 5, 25> = Game::with_seed(
+                seed,
+                GameConfig {
+                    tail: TailRule::Chase,
+                    ..Default::default()
+                },
+            );
+            play(&mut game, &mut BfsBot);
+            assert!(
+                game.food_eaten() >= 10,
+                "seed {seed}: {}",
+                game.food_eaten()
+            );
+        }
+    }
+}
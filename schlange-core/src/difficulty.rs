@@ -0,0 +1,140 @@
+use crate::{
+    boundary::{BoundaryMode, CROSS_PORTALS},
+    draw::Canvas,
+    food::FoodMode,
+    game::TailRule,
+    input::ButtonCode,
+    snapshot::{CellState, Snapshot},
+    speed::{Ramp, SpeedCurve},
+};
+
+/// Everything a difficulty decides about a game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DifficultyProfile {
+    pub name: &'static str,
+    pub speed: SpeedCurve,
+    /// The boundary mode the menu offers first.
+    pub boundary: BoundaryMode,
+    pub food: FoodMode,
+    pub tail: TailRule,
+    pub score_multiplier: u32,
+}
+
+/// The difficulties from the easiest to the hardest.
+///
+/// The easy ones speed up gently and late, only put food where the snake can get to it
+/// and let the snake chase its tail, the hard ones start fast, get to full speed soon
+/// and keep the food away from the head.
+#[rustfmt::skip]
+pub const PROFILES: &[DifficultyProfile] = &[
+    DifficultyProfile { name: "EASY", speed: SpeedCurve::new(1000, 400, Ramp::EaseIn, 20), boundary: BoundaryMode::Wrap, food: FoodMode::Reachable, tail: TailRule::Chase, score_multiplier: 1 },
+    DifficultyProfile { name: "NORMAL", speed: SpeedCurve::new(500, 250, Ramp::Linear, 20), boundary: BoundaryMode::Wrap, food: FoodMode::Reachable, tail: TailRule::Chase, score_multiplier: 2 },
+    DifficultyProfile { name: "HARD", speed: SpeedCurve::new(400, 180, Ramp::Linear, 16), boundary: BoundaryMode::Walls, food: FoodMode::Uniform, tail: TailRule::Strict, score_multiplier: 3 },
+    DifficultyProfile { name: "INSANE", speed: SpeedCurve::new(300, 130, Ramp::EaseOut, 14), boundary: BoundaryMode::Portals(&CROSS_PORTALS), food: FoodMode::NotAdjacent, tail: TailRule::Strict, score_multiplier: 4 },
+    DifficultyProfile { name: "HELL", speed: SpeedCurve::new(200, 90, Ramp::EaseOut, 12), boundary: BoundaryMode::Walls, food: FoodMode::Far, tail: TailRule::Strict, score_multiplier: 5 },
+];
+
+// the high score record has a score per profile
+pub const DIFFICULTY_COUNT: usize = PROFILES.len();
+
+// NORMAL
+const DEFAULT_PROFILE: usize = 1;
+
+pub struct DifficultySelector {
+    index: usize,
+}
+
+impl DifficultySelector {
+    pub fn new() -> Self {
+        Self::with_index(DEFAULT_PROFILE)
+    }
+
+    /// A selector that offers `PROFILES[index]` first, the default one if there is no
+    /// such profile.
+    pub fn with_index(index: usize) -> Self {
+        DifficultySelector {
+            index: if index < PROFILES.len() {
+                index
+            } else {
+                DEFAULT_PROFILE
+            },
+        }
+    }
+    pub fn is_choice_made(&mut self, input: ButtonCode) -> Option<()> {
+        match input {
+            ButtonCode::PressedA => {
+                self.index = (self.index + 1) % PROFILES.len();
+                None
+            }
+            ButtonCode::PressedB => Some(()),
+            _ => None,
+        }
+    }
+
+    /// Position of the selected profile in `PROFILES`, from 0 for the easiest one to
+    /// `DIFFICULTY_COUNT - 1`.
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
+    pub fn get_profile(&self) -> &'static DifficultyProfile {
+        &PROFILES[self.index]
+    }
+
+    /// A blinking bar in the middle, as high as the score multiplier of the profile.
+    pub fn get_snapshot(&self) -> Snapshot<5, 5> {
+        let height = self.get_profile().score_multiplier as usize;
+        Canvas::new(CellState::AnimationBlinking)
+            .bar_graph(&[height; 3])
+            .finish()
+    }
+}
+
+impl Default for DifficultySelector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_rotates_and_b_confirms() {
+        let mut selector = DifficultySelector::new();
+        assert_eq!(selector.get_profile().speed.start_ms, 500);
+        assert!(selector.is_choice_made(ButtonCode::PressedA).is_none());
+        assert_eq!(selector.get_profile().speed.start_ms, 400);
+        for _ in 0..3 {
+            selector.is_choice_made(ButtonCode::PressedA);
+        }
+        assert_eq!(selector.get_profile().speed.start_ms, 1000);
+        assert!(selector.is_choice_made(ButtonCode::PressedB).is_some());
+        assert_eq!(selector.get_profile().speed.start_ms, 1000);
+    }
+
+    #[test]
+    fn icon_bar_grows_with_the_multiplier() {
+        let mut selector = DifficultySelector::new();
+        let lit = |selector: &DifficultySelector| {
+            selector
+                .get_snapshot()
+                .buffer
+                .iter()
+                .flatten()
+                .filter(|&&cell| cell == CellState::AnimationBlinking)
+                .count()
+        };
+        assert_eq!(lit(&selector), 6);
+        for _ in 0..3 {
+            selector.is_choice_made(ButtonCode::PressedA);
+        }
+        assert_eq!(lit(&selector), 15);
+        assert_eq!(
+            selector.get_snapshot().buffer[2][0],
+            CellState::AnimationBlinking
+        );
+        assert_eq!(selector.get_snapshot().buffer[0][4], CellState::Empty);
+    }
+}
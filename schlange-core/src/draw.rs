@@ -0,0 +1,122 @@
+use crate::{
+    font::glyph,
+    snapshot::{CellState, Snapshot},
+};
+
+/// Draws menu screens out of shapes instead of writing them down cell by cell.
+///
+/// Everything is drawn with the same `ink`, shapes are clipped at the edge of the board.
+pub struct Canvas<const NCOLS: usize, const NROWS: usize> {
+    snapshot: Snapshot<NCOLS, NROWS>,
+    ink: CellState,
+}
+
+impl<const NCOLS: usize, const NROWS: usize> Canvas<NCOLS, NROWS> {
+    pub fn new(ink: CellState) -> Self {
+        Canvas {
+            snapshot: Snapshot::new(),
+            ink,
+        }
+    }
+
+    pub fn plot(mut self, col: usize, row: usize) -> Self {
+        if let Some(cell) = self
+            .snapshot
+            .buffer
+            .get_mut(col)
+            .and_then(|cells| cells.get_mut(row))
+        {
+            *cell = self.ink;
+        }
+        self
+    }
+
+    /// Columns of pixels with the top row in bit 0, the way the font is stored, starting
+    /// at column `col`.
+    pub fn bitmap(self, col: usize, columns: &[u8]) -> Self {
+        columns
+            .iter()
+            .enumerate()
+            .fold(self, |canvas, (offset, &pixels)| {
+                (0..NROWS.min(8))
+                    .filter(|&row| pixels & (1 << row) != 0)
+                    .fold(canvas, |canvas, row| canvas.plot(col + offset, row))
+            })
+    }
+
+    /// Like `bitmap`, but centred horizontally.
+    pub fn centred_bitmap(self, columns: &[u8]) -> Self {
+        self.bitmap(NCOLS.saturating_sub(columns.len()) / 2, columns)
+    }
+
+    /// The glyph of `c` in the middle of the board, nothing if the font lacks it.
+    pub fn glyph(self, c: char) -> Self {
+        match glyph(c) {
+            Some(columns) => self.centred_bitmap(columns),
+            None => self,
+        }
+    }
+
+    /// One bar per entry of `heights`, standing on the bottom edge, centred horizontally.
+    pub fn bar_graph(self, heights: &[usize]) -> Self {
+        let left = NCOLS.saturating_sub(heights.len()) / 2;
+        heights
+            .iter()
+            .enumerate()
+            .fold(self, |canvas, (index, &height)| {
+                (NROWS.saturating_sub(height)..NROWS)
+                    .fold(canvas, |canvas, row| canvas.plot(left + index, row))
+            })
+    }
+
+    pub fn finish(self) -> Snapshot<NCOLS, NROWS> {
+        self.snapshot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INK: CellState = CellState::AnimationBlinking;
+
+    fn picture(snapshot: &Snapshot<5, 5>) -> [String; 5] {
+        core::array::from_fn(|row| {
+            (0..5)
+                .map(|col| match snapshot.buffer[col][row] {
+                    CellState::Empty => '.',
+                    _ => '#',
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn bar_graph_stands_on_the_bottom() {
+        let snapshot = Canvas::new(INK).bar_graph(&[1, 3, 2]).finish();
+        assert_eq!(
+            picture(&snapshot),
+            [".....", ".....", "..#..", "..##.", ".###."]
+        );
+    }
+
+    #[test]
+    fn glyph_is_centred() {
+        let snapshot = Canvas::new(INK).glyph('7').finish();
+        assert_eq!(
+            picture(&snapshot),
+            [".###.", "...#.", "..#..", "..#..", "..#.."]
+        );
+        assert_eq!(snapshot.buffer[2][2], INK);
+    }
+
+    #[test]
+    fn shapes_are_clipped_at_the_edge() {
+        let snapshot: Snapshot<5, 5> = Canvas::new(INK)
+            .bitmap(3, &[0b11111, 0b00001, 0b00001])
+            .bar_graph(&[9; 7])
+            .plot(5, 0)
+            .finish();
+        assert!(snapshot.buffer.iter().flatten().all(|&cell| cell == INK));
+    }
+}
@@ -0,0 +1,74 @@
+pub const GLYPH_HEIGHT: usize = 5;
+
+/// The columns of the glyph for `c`, the top row is bit 0. Letters are upper case only,
+/// lower case ones are mapped onto them.
+pub fn glyph(c: char) -> Option<&'static [u8]> {
+    let columns: &'static [u8] = match c.to_ascii_uppercase() {
+        '0' => &[0b11111, 0b10001, 0b11111],
+        '1' => &[0b10010, 0b11111, 0b10000],
+        '2' => &[0b11101, 0b10101, 0b10111],
+        '3' => &[0b10101, 0b10101, 0b11111],
+        '4' => &[0b00111, 0b00100, 0b11111],
+        '5' => &[0b10111, 0b10101, 0b11101],
+        '6' => &[0b11111, 0b10101, 0b11101],
+        '7' => &[0b00001, 0b11101, 0b00011],
+        '8' => &[0b11111, 0b10101, 0b11111],
+        '9' => &[0b10111, 0b10101, 0b11111],
+        'A' => &[0b11110, 0b00101, 0b11110],
+        'B' => &[0b11111, 0b10101, 0b01010],
+        'C' => &[0b01110, 0b10001, 0b10001],
+        'D' => &[0b11111, 0b10001, 0b01110],
+        'E' => &[0b11111, 0b10101, 0b10001],
+        'F' => &[0b11111, 0b00101, 0b00001],
+        'G' => &[0b01110, 0b10001, 0b11101],
+        'H' => &[0b11111, 0b00100, 0b11111],
+        'I' => &[0b10001, 0b11111, 0b10001],
+        'J' => &[0b01000, 0b10000, 0b01111],
+        'K' => &[0b11111, 0b00100, 0b11011],
+        'L' => &[0b11111, 0b10000, 0b10000],
+        'M' => &[0b11111, 0b00010, 0b00100, 0b00010, 0b11111],
+        'N' => &[0b11111, 0b00010, 0b00100, 0b11111],
+        'O' => &[0b01110, 0b10001, 0b01110],
+        'P' => &[0b11111, 0b00101, 0b00010],
+        'Q' => &[0b01110, 0b10001, 0b01110, 0b10000],
+        'R' => &[0b11111, 0b00101, 0b11010],
+        'S' => &[0b10010, 0b10101, 0b01001],
+        'T' => &[0b00001, 0b11111, 0b00001],
+        'U' => &[0b11111, 0b10000, 0b11111],
+        'V' => &[0b01111, 0b10000, 0b01111],
+        'W' => &[0b11111, 0b01000, 0b00100, 0b01000, 0b11111],
+        'X' => &[0b11011, 0b00100, 0b11011],
+        'Y' => &[0b00011, 0b11100, 0b00011],
+        'Z' => &[0b11001, 0b10101, 0b10011],
+        ' ' => &[0b00000, 0b00000],
+        '!' => &[0b10111],
+        '?' => &[0b00001, 0b10101, 0b00111],
+        '-' => &[0b00100, 0b00100, 0b00100],
+        '+' => &[0b00100, 0b01110, 0b00100],
+        '.' => &[0b10000],
+        ':' => &[0b01010],
+        '<' => &[0b00100, 0b01010, 0b10001],
+        _ => return None,
+    };
+    Some(columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glyphs_fit_the_display() {
+        for c in ('0'..='9').chain('A'..='Z').chain(" !?-+.:<".chars()) {
+            let columns = glyph(c).unwrap();
+            assert!((1..=5).contains(&columns.len()));
+            assert!(columns.iter().all(|column| column >> GLYPH_HEIGHT == 0));
+        }
+        assert!(glyph('~').is_none());
+    }
+
+    #[test]
+    fn lower_case_maps_to_upper_case() {
+        assert_eq!(glyph('s'), glyph('S'));
+    }
+}
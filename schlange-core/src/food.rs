@@ -0,0 +1,328 @@
+use heapless::Vec;
+
+use crate::{
+    boundary::BoundaryMode,
+    game::{Coordinate, Direction},
+    rng::Rng,
+};
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::North,
+    Direction::Ost,
+    Direction::South,
+    Direction::West,
+];
+
+/// What a `FoodSpawner` gets to see of the board.
+pub struct Board<'a, const NCOLS: usize, const NROWS: usize> {
+    /// The cells covered by the snake or a wall, indexed by column first.
+    pub occupied: &'a [[bool; NROWS]; NCOLS],
+    pub head: Coordinate,
+    pub boundary: BoundaryMode,
+}
+
+impl<'a, const NCOLS: usize, const NROWS: usize> Board<'a, NCOLS, NROWS> {
+    pub fn is_free(&self, coordinate: Coordinate) -> bool {
+        !self.occupied[coordinate.col as usize][coordinate.row as usize]
+    }
+
+    /// The cells without snake or wall, column by column.
+    pub fn free_cells(&self) -> impl Iterator<Item = Coordinate> + Clone + '_ {
+        (0..NCOLS)
+            .flat_map(|col| (0..NROWS).map(move |row| (col, row)))
+            .map(|(col, row)| Coordinate {
+                row: row as u8,
+                col: col as u8,
+            })
+            .filter(|&coordinate| self.is_free(coordinate))
+    }
+
+    /// The cells one move away from `coordinate`, following the boundary mode.
+    pub fn neighbours(&self, coordinate: Coordinate) -> impl Iterator<Item = Coordinate> + '_ {
+        DIRECTIONS.into_iter().filter_map(move |direction| {
+            self.boundary
+                .advance::<NCOLS, NROWS>(coordinate, direction)
+                .map(|(neighbour, _)| neighbour)
+        })
+    }
+
+    /// The number of moves from the head to every free cell it can reach without
+    /// running into the snake, as the snake lies now.
+    pub fn distances(&self) -> [[Option<u16>; NROWS]; NCOLS] {
+        let mut distances = [[None; NROWS]; NCOLS];
+        distances[self.head.col as usize][self.head.row as usize] = Some(0);
+        // grow the reached area one move at a time, the boards are too small for a queue
+        // to pay off
+        let mut distance = 0;
+        let mut grown = true;
+        while grown {
+            grown = false;
+            for col in 0..NCOLS {
+                for row in 0..NROWS {
+                    if distances[col][row] != Some(distance) {
+                        continue;
+                    }
+                    let coordinate = Coordinate {
+                        row: row as u8,
+                        col: col as u8,
+                    };
+                    for neighbour in self.neighbours(coordinate) {
+                        let cell = &mut distances[neighbour.col as usize][neighbour.row as usize];
+                        if cell.is_none() && self.is_free(neighbour) {
+                            *cell = Some(distance + 1);
+                            grown = true;
+                        }
+                    }
+                }
+            }
+            distance += 1;
+        }
+        distances
+    }
+}
+
+/// Decides where the next food goes.
+pub trait FoodSpawner {
+    /// One of the free cells of `board`, `None` if there is none.
+    fn spawn<const NCOLS: usize, const NROWS: usize>(
+        &self,
+        board: &Board<NCOLS, NROWS>,
+        rng: &mut impl Rng,
+    ) -> Option<Coordinate>;
+}
+
+/// Every free cell is equally likely.
+pub struct Uniform;
+
+/// Any free cell but the ones the head can move to next.
+pub struct NotAdjacent;
+
+/// Only free cells the head can reach without running into the snake.
+pub struct Reachable;
+
+/// The reachable free cells farthest away from the head.
+pub struct Far;
+
+impl FoodSpawner for Uniform {
+    fn spawn<const NCOLS: usize, const NROWS: usize>(
+        &self,
+        board: &Board<NCOLS, NROWS>,
+        rng: &mut impl Rng,
+    ) -> Option<Coordinate> {
+        pick(board.free_cells(), rng)
+    }
+}
+
+// the restricted spawners fall back to any free cell, there is always food while
+// there is room for it
+
+impl FoodSpawner for NotAdjacent {
+    fn spawn<const NCOLS: usize, const NROWS: usize>(
+        &self,
+        board: &Board<NCOLS, NROWS>,
+        rng: &mut impl Rng,
+    ) -> Option<Coordinate> {
+        let adjacent: Vec<Coordinate, 4> = board.neighbours(board.head).collect();
+        let candidates = board
+            .free_cells()
+            .filter(|coordinate| !adjacent.contains(coordinate));
+        pick(candidates, rng).or_else(|| Uniform.spawn(board, rng))
+    }
+}
+
+impl FoodSpawner for Reachable {
+    fn spawn<const NCOLS: usize, const NROWS: usize>(
+        &self,
+        board: &Board<NCOLS, NROWS>,
+        rng: &mut impl Rng,
+    ) -> Option<Coordinate> {
+        let distances = board.distances();
+        let candidates = board
+            .free_cells()
+            .filter(|&coordinate| distance(&distances, coordinate).is_some());
+        pick(candidates, rng).or_else(|| Uniform.spawn(board, rng))
+    }
+}
+
+impl FoodSpawner for Far {
+    fn spawn<const NCOLS: usize, const NROWS: usize>(
+        &self,
+        board: &Board<NCOLS, NROWS>,
+        rng: &mut impl Rng,
+    ) -> Option<Coordinate> {
+        let distances = board.distances();
+        let farthest = board
+            .free_cells()
+            .filter_map(|coordinate| distance(&distances, coordinate))
+            .max();
+        let candidates = board.free_cells().filter(|&coordinate| {
+            farthest.is_some_and(|far| distance(&distances, coordinate) == Some(far))
+        });
+        pick(candidates, rng).or_else(|| Uniform.spawn(board, rng))
+    }
+}
+
+/// Where the food of a game is placed.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FoodMode {
+    #[default]
+    Uniform,
+    NotAdjacent,
+    Reachable,
+    Far,
+}
+
+impl FoodSpawner for FoodMode {
+    fn spawn<const NCOLS: usize, const NROWS: usize>(
+        &self,
+        board: &Board<NCOLS, NROWS>,
+        rng: &mut impl Rng,
+    ) -> Option<Coordinate> {
+        match self {
+            FoodMode::Uniform => Uniform.spawn(board, rng),
+            FoodMode::NotAdjacent => NotAdjacent.spawn(board, rng),
+            FoodMode::Reachable => Reachable.spawn(board, rng),
+            FoodMode::Far => Far.spawn(board, rng),
+        }
+    }
+}
+
+fn distance<const NCOLS: usize, const NROWS: usize>(
+    distances: &[[Option<u16>; NROWS]; NCOLS],
+    coordinate: Coordinate,
+) -> Option<u16> {
+    distances[coordinate.col as usize][coordinate.row as usize]
+}
+
+/// One of `candidates`, all equally likely.
+fn pick(
+    mut candidates: impl Iterator<Item = Coordinate> + Clone,
+    rng: &mut impl Rng,
+) -> Option<Coordinate> {
+    let count = candidates.clone().count() as u32;
+    if count == 0 {
+        return None;
+    }
+    candidates.nth(rng.next_below(count) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::SeededRng;
+
+    const SAMPLES: u32 = 24_000;
+
+    fn board_with_snake(
+        snake: &[(u8, u8)],
+        boundary: BoundaryMode,
+    ) -> ([[bool; 5]; 5], Coordinate, BoundaryMode) {
+        let mut occupied = [[false; 5]; 5];
+        for &(row, col) in snake {
+            occupied[col as usize][row as usize] = true;
+        }
+        let (row, col) = snake[0];
+        (occupied, Coordinate { row, col }, boundary)
+    }
+
+    /// How often every cell got the food in `SAMPLES` spawns.
+    fn spawn_counts(
+        spawner: &impl FoodSpawner,
+        (occupied, head, boundary): &([[bool; 5]; 5], Coordinate, BoundaryMode),
+    ) -> [[u32; 5]; 5] {
+        let board = Board {
+            occupied,
+            head: *head,
+            boundary: *boundary,
+        };
+        let mut rng = SeededRng::new(2024);
+        let mut counts = [[0; 5]; 5];
+        for _ in 0..SAMPLES {
+            let food = spawner.spawn(&board, &mut rng).unwrap();
+            assert!(board.is_free(food));
+            counts[food.col as usize][food.row as usize] += 1;
+        }
+        counts
+    }
+
+    /// Asserts the food landed on exactly the `expected` cells, all equally often.
+    fn assert_uniform_over(counts: &[[u32; 5]; 5], expected: impl Fn(u8, u8) -> bool) {
+        let mut hits = std::vec::Vec::new();
+        for (col, rows) in counts.iter().enumerate() {
+            for (row, &count) in rows.iter().enumerate() {
+                if expected(row as u8, col as u8) {
+                    hits.push(count);
+                } else {
+                    assert_eq!(count, 0, "food at row {row} col {col}");
+                }
+            }
+        }
+        let mean = SAMPLES as f64 / hits.len() as f64;
+        let chi_squared: f64 = hits
+            .iter()
+            .map(|&count| (count as f64 - mean).powi(2) / mean)
+            .sum();
+        // four standard deviations above the mean of the chi-squared distribution, the
+        // seed is fixed so this never flakes but still catches a skewed spawner
+        let freedom = (hits.len() - 1) as f64;
+        assert!(
+            chi_squared < freedom + 4.0 * (2.0 * freedom).sqrt(),
+            "{counts:?}"
+        );
+    }
+
+    #[test]
+    fn uniform_covers_every_free_cell_evenly() {
+        let board = board_with_snake(&[(2, 2), (3, 2)], BoundaryMode::Wrap);
+        let counts = spawn_counts(&Uniform, &board);
+        assert_uniform_over(&counts, |row, col| col != 2 || !(2..=3).contains(&row));
+    }
+
+    #[test]
+    fn not_adjacent_keeps_off_the_next_moves() {
+        let board = board_with_snake(&[(0, 0)], BoundaryMode::Wrap);
+        let counts = spawn_counts(&NotAdjacent, &board);
+        // on the torus the cells across the edges are next to the head as well
+        let adjacent = [(0, 0), (1, 0), (4, 0), (0, 1), (0, 4)];
+        assert_uniform_over(&counts, |row, col| !adjacent.contains(&(row, col)));
+    }
+
+    #[test]
+    fn reachable_stays_on_the_side_of_the_head() {
+        // the snake walls the rightmost two columns off from the head
+        let snake = [(2, 1), (2, 2), (1, 2), (0, 2), (3, 2), (4, 2)];
+        let board = board_with_snake(&snake, BoundaryMode::Walls);
+        let counts = spawn_counts(&Reachable, &board);
+        assert_uniform_over(&counts, |row, col| col < 2 && (row, col) != (2, 1));
+    }
+
+    #[test]
+    fn far_picks_the_cells_farthest_away() {
+        let board = board_with_snake(&[(2, 2)], BoundaryMode::Wrap);
+        let counts = spawn_counts(&Far, &board);
+        let corners = [(0, 0), (0, 4), (4, 0), (4, 4)];
+        assert_uniform_over(&counts, |row, col| corners.contains(&(row, col)));
+    }
+
+    #[test]
+    fn restricted_spawners_fall_back_to_any_free_cell() {
+        let mut occupied = [[true; 5]; 5];
+        occupied[4][4] = false;
+        let board = Board {
+            occupied: &occupied,
+            head: Coordinate { row: 0, col: 0 },
+            boundary: BoundaryMode::Walls,
+        };
+        let mut rng = SeededRng::new(1);
+        let last = Some(Coordinate { row: 4, col: 4 });
+        assert_eq!(NotAdjacent.spawn(&board, &mut rng), last);
+        assert_eq!(Reachable.spawn(&board, &mut rng), last);
+        assert_eq!(Far.spawn(&board, &mut rng), last);
+        let full = [[true; 5]; 5];
+        let board = Board {
+            occupied: &full,
+            ..board
+        };
+        assert_eq!(FoodMode::Far.spawn(&board, &mut rng), None);
+    }
+}
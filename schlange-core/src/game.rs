@@ -0,0 +1,645 @@
+use crate::{
+    boundary::BoundaryMode,
+    food::{Board, FoodMode, FoodSpawner},
+    input::ButtonCode,
+    level::Level,
+    rb::RingBuffer,
+    rng::{Rng, SeededRng},
+    snapshot::{CellState, Snapshot},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    North,
+    Ost,
+    South,
+    West,
+}
+
+impl Direction {
+    /// The heading after a turn to the left, what `ButtonCode::PressedA` does.
+    pub fn left(self) -> Self {
+        match self {
+            Direction::North => Direction::West,
+            Direction::Ost => Direction::North,
+            Direction::South => Direction::Ost,
+            Direction::West => Direction::South,
+        }
+    }
+
+    /// The heading after a turn to the right, what `ButtonCode::PressedB` does.
+    pub fn right(self) -> Self {
+        match self {
+            Direction::North => Direction::Ost,
+            Direction::Ost => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        self.left().left()
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Coordinate {
+    pub row: u8,
+    pub col: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameConfig {
+    pub boundary: BoundaryMode,
+    /// Points for every food eaten.
+    pub score_multiplier: u32,
+    pub food: FoodMode,
+    pub tail: TailRule,
+    /// Walls on the board and when the level is cleared, an empty board without end if `None`.
+    pub level: Option<&'static Level>,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            boundary: Default::default(),
+            score_multiplier: 1,
+            food: Default::default(),
+            tail: Default::default(),
+            level: None,
+        }
+    }
+}
+
+/// Whether the head may move into the cell the tail leaves in the same tick.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TailRule {
+    /// The head hits the tail before it moves on.
+    #[default]
+    Strict,
+    /// The tail moves on first, the snake can chase its own tail.
+    Chase,
+}
+
+/// A snake game on an `NCOLS` x `NROWS` board, `NCELLS` has to be `NCOLS * NROWS`.
+/// All randomness comes from `R`, the same seed and inputs give the same game.
+pub struct Game<const NCOLS: usize, const NROWS: usize, const NCELLS: usize, R = SeededRng> {
+    config: GameConfig,
+    snake: RingBuffer<Coordinate, NCELLS>,
+    // the cells covered by the snake or a wall
+    occupied: [[bool; NROWS]; NCOLS],
+    direction: Direction,
+    food: Coordinate,
+    food_eaten: u32,
+    rng: R,
+}
+
+#[derive(Debug)]
+pub enum SnakeError {
+    Fatal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveResult {
+    Trivial,
+    BiteYourself,
+    HitWall,
+    Win,
+    /// Enough food was eaten to move on to the next level.
+    LevelCleared,
+}
+
+impl<const NCOLS: usize, const NROWS: usize, const NCELLS: usize> Game<NCOLS, NROWS, NCELLS> {
+    pub fn with_seed(seed: u32, config: GameConfig) -> Self {
+        Self::new(SeededRng::new(seed), config)
+    }
+}
+
+impl<const NCOLS: usize, const NROWS: usize, const NCELLS: usize, R: Rng>
+    Game<NCOLS, NROWS, NCELLS, R>
+{
+    pub fn new(rng: R, config: GameConfig) -> Self {
+        // coordinates are u8
+        const { assert!(NCOLS * NROWS == NCELLS && NCOLS <= 256 && NROWS <= 256) };
+        let mut game = Game {
+            config,
+            direction: Direction::North,
+            snake: RingBuffer::new(),
+            occupied: [[false; NROWS]; NCOLS],
+            rng,
+            food: Default::default(),
+            food_eaten: 0,
+        };
+
+        for coordinate in game.cells() {
+            if game.is_wall(coordinate) {
+                game.occupy(coordinate, true);
+            }
+        }
+        game.snake_add_head(Coordinate {
+            row: (NROWS / 2) as u8,
+            col: (NCOLS / 2) as u8,
+        })
+        .unwrap();
+        game.give_food();
+        game
+    }
+
+    /// Advances the game by one tick, applying the optional turn first.
+    pub fn step(&mut self, input: Option<ButtonCode>) -> MoveResult {
+        if let Some(input) = input {
+            self.update_direction(input);
+        }
+        self.do_move().unwrap()
+    }
+
+    pub fn head(&self) -> Coordinate {
+        self.snake.peek_head()
+    }
+
+    pub fn len(&self) -> usize {
+        self.snake.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snake.is_empty()
+    }
+
+    pub fn food(&self) -> Coordinate {
+        self.food
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    pub fn food_eaten(&self) -> u32 {
+        self.food_eaten
+    }
+
+    pub fn score(&self) -> u32 {
+        self.food_eaten * self.config.score_multiplier
+    }
+
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    /// The board as it is now, snake and walls are taken.
+    pub fn board(&self) -> Board<'_, NCOLS, NROWS> {
+        Board {
+            occupied: &self.occupied,
+            head: self.snake.peek_head(),
+            boundary: self.config.boundary,
+        }
+    }
+
+    fn is_snake(&self, coordinate: Coordinate) -> bool {
+        self.occupied[coordinate.col as usize][coordinate.row as usize]
+    }
+
+    fn is_board_full(&self) -> bool {
+        self.occupied.iter().flatten().all(|&occupied| occupied)
+    }
+
+    fn is_wall(&self, coordinate: Coordinate) -> bool {
+        self.config
+            .level
+            .is_some_and(|level| level.is_wall(coordinate))
+    }
+
+    fn cells(&self) -> impl Iterator<Item = Coordinate> {
+        (0..NCOLS)
+            .flat_map(|col| (0..NROWS).map(move |row| (col, row)))
+            .map(|(col, row)| Coordinate {
+                row: row as u8,
+                col: col as u8,
+            })
+    }
+
+    fn give_food(&mut self) {
+        let board = Board {
+            occupied: &self.occupied,
+            head: self.snake.peek_head(),
+            boundary: self.config.boundary,
+        };
+        if let Some(food) = self.config.food.spawn(&board, &mut self.rng) {
+            self.food = food;
+        }
+    }
+
+    pub fn update_direction(&mut self, input: ButtonCode) {
+        match input {
+            // the second tap of a double press is a turn of its own
+            ButtonCode::PressedA | ButtonCode::DoublePressedA => {
+                self.direction = self.direction.left()
+            }
+            ButtonCode::PressedB | ButtonCode::DoublePressedB => {
+                self.direction = self.direction.right()
+            }
+            _ => (),
+        }
+    }
+
+    fn get_new_head_coordinate(&self) -> Option<(Coordinate, Direction)> {
+        self.config
+            .boundary
+            .advance::<NCOLS, NROWS>(self.snake.peek_head(), self.direction)
+    }
+
+    fn is_food(&self, coordinate: Coordinate) -> bool {
+        self.food == coordinate
+    }
+
+    /// Whether the tail leaves `coordinate` before the head gets there.
+    fn is_vacated(&self, coordinate: Coordinate) -> bool {
+        self.config.tail == TailRule::Chase && self.snake.peek_tail() == coordinate
+    }
+
+    pub fn do_move(&mut self) -> Result<MoveResult, SnakeError> {
+        let Some((new_head, direction)) = self.get_new_head_coordinate() else {
+            return Ok(MoveResult::HitWall);
+        };
+        if self.is_wall(new_head) {
+            Ok(MoveResult::HitWall)
+        } else if self.is_snake(new_head) && !self.is_vacated(new_head) {
+            Ok(MoveResult::BiteYourself)
+        } else {
+            self.direction = direction;
+            let eating = self.is_food(new_head);
+            if !eating {
+                self.snake_cut_tail()?;
+            }
+            self.snake_add_head(new_head)?;
+            if eating {
+                self.food_eaten += 1;
+                if self.is_board_full() {
+                    return Ok(MoveResult::Win);
+                }
+                self.give_food();
+                if self
+                    .config
+                    .level
+                    .is_some_and(|level| self.food_eaten >= level.food_to_clear)
+                {
+                    return Ok(MoveResult::LevelCleared);
+                }
+            }
+            Ok(MoveResult::Trivial)
+        }
+    }
+
+    pub fn get_snapshot(&self) -> Snapshot<NCOLS, NROWS> {
+        let mut snapshot = Snapshot::new();
+
+        for coordinate in self.cells().filter(|&coordinate| self.is_wall(coordinate)) {
+            snapshot.buffer[coordinate.col as usize][coordinate.row as usize] = CellState::Wall;
+        }
+
+        let mut snake_iter = self.snake.iter();
+        let head = snake_iter.next().unwrap();
+        snapshot.buffer[head.col as usize][head.row as usize] = CellState::SnakeHead;
+        for tail in snake_iter {
+            snapshot.buffer[tail.col as usize][tail.row as usize] = CellState::SnakeTail;
+        }
+        // a full board has no room left for food
+        if !self.is_snake(self.food) {
+            snapshot.buffer[self.food.col as usize][self.food.row as usize] = CellState::Food;
+        }
+
+        snapshot
+    }
+
+    fn snake_add_head(&mut self, coordinate: Coordinate) -> Result<(), SnakeError> {
+        self.occupy(coordinate, true);
+        // the snake never covers more than the board, so the buffer can't run out of room
+        self.snake.put(coordinate).map_err(|_| SnakeError::Fatal)
+    }
+
+    fn snake_cut_tail(&mut self) -> Result<MoveResult, SnakeError> {
+        let tail = self.snake.get().map_err(|_| SnakeError::Fatal)?;
+        self.occupy(tail, false);
+        Ok(MoveResult::Trivial)
+    }
+
+    fn occupy(&mut self, coordinate: Coordinate, occupied: bool) {
+        self.occupied[coordinate.col as usize][coordinate.row as usize] = occupied;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary::Portal;
+
+    type Game = super::Game<5, 5, 25>;
+
+    #[test]
+    fn starts_in_the_middle_heading_north() {
+        let game = Game::with_seed(1, GameConfig::default());
+        assert_eq!(game.head(), Coordinate { row: 2, col: 2 });
+        assert_eq!(game.direction(), Direction::North);
+        assert_eq!(game.len(), 1);
+        assert_ne!(game.food(), game.head());
+    }
+
+    #[test]
+    fn turns_are_relative_to_the_heading() {
+        let mut game = Game::with_seed(1, GameConfig::default());
+        game.update_direction(ButtonCode::PressedA);
+        assert_eq!(game.direction(), Direction::West);
+        game.update_direction(ButtonCode::PressedA);
+        assert_eq!(game.direction(), Direction::South);
+        game.update_direction(ButtonCode::PressedB);
+        assert_eq!(game.direction(), Direction::West);
+        game.update_direction(ButtonCode::PressedB);
+        assert_eq!(game.direction(), Direction::North);
+    }
+
+    #[test]
+    fn double_press_turns_twice() {
+        let mut game = Game::with_seed(1, GameConfig::default());
+        game.update_direction(ButtonCode::PressedA);
+        game.update_direction(ButtonCode::DoublePressedA);
+        assert_eq!(game.direction(), Direction::South);
+        game.update_direction(ButtonCode::PressedB);
+        game.update_direction(ButtonCode::DoublePressedB);
+        assert_eq!(game.direction(), Direction::North);
+    }
+
+    #[test]
+    fn head_wraps_around_the_board() {
+        let mut game = Game::with_seed(1, GameConfig::default());
+        // keep the food out of the way of the column we travel along
+        game.food = Coordinate { row: 0, col: 0 };
+        for row in [1, 0, 4, 3, 2] {
+            assert_eq!(game.step(None), MoveResult::Trivial);
+            assert_eq!(game.head(), Coordinate { row, col: 2 });
+        }
+    }
+
+    #[test]
+    fn eating_grows_the_snake() {
+        let mut game = Game::with_seed(1, GameConfig::default());
+        game.food = Coordinate { row: 1, col: 2 };
+        assert_eq!(game.step(None), MoveResult::Trivial);
+        assert_eq!(game.len(), 2);
+        assert_ne!(game.food(), Coordinate { row: 1, col: 2 });
+        assert!(!game.is_snake(game.food()));
+    }
+
+    #[test]
+    fn turning_into_the_body_bites_yourself() {
+        let mut game = Game::with_seed(1, GameConfig::default());
+        for row in [1, 0, 4] {
+            game.food = Coordinate { row, col: 2 };
+            assert_eq!(game.step(None), MoveResult::Trivial);
+        }
+        game.food = Coordinate { row: 2, col: 0 };
+        assert_eq!(game.len(), 4);
+        assert_eq!(game.step(Some(ButtonCode::PressedB)), MoveResult::Trivial);
+        assert_eq!(game.step(Some(ButtonCode::PressedB)), MoveResult::Trivial);
+        assert_eq!(
+            game.step(Some(ButtonCode::PressedB)),
+            MoveResult::BiteYourself
+        );
+    }
+
+    #[test]
+    fn snapshot_shows_head_and_food() {
+        let game = Game::with_seed(1, GameConfig::default());
+        let snapshot = game.get_snapshot();
+        assert_eq!(snapshot.buffer[2][2], CellState::SnakeHead);
+        let food = game.food();
+        assert_eq!(
+            snapshot.buffer[food.col as usize][food.row as usize],
+            CellState::Food
+        );
+    }
+
+    #[test]
+    fn non_square_board_wraps_per_axis() {
+        let mut game: super::Game<8, 3, 24> = super::Game::with_seed(7, GameConfig::default());
+        assert_eq!(game.head(), Coordinate { row: 1, col: 4 });
+        game.food = Coordinate { row: 0, col: 0 };
+        game.update_direction(ButtonCode::PressedB);
+        for col in [5, 6, 7, 0, 1, 2, 3, 4] {
+            assert_eq!(game.step(None), MoveResult::Trivial);
+            assert_eq!(game.head(), Coordinate { row: 1, col });
+        }
+        game.update_direction(ButtonCode::PressedB);
+        for row in [2, 0, 1] {
+            assert_eq!(game.step(None), MoveResult::Trivial);
+            assert_eq!(game.head(), Coordinate { row, col: 4 });
+        }
+        let snapshot = game.get_snapshot();
+        assert_eq!(snapshot.buffer.len(), 8);
+        assert_eq!(snapshot.buffer[4][1], CellState::SnakeHead);
+    }
+
+    #[test]
+    fn walls_end_the_game_at_the_edge() {
+        let mut game = Game::with_seed(
+            1,
+            GameConfig {
+                boundary: BoundaryMode::Walls,
+                ..Default::default()
+            },
+        );
+        game.food = Coordinate { row: 4, col: 4 };
+        assert_eq!(game.step(None), MoveResult::Trivial);
+        assert_eq!(game.step(None), MoveResult::Trivial);
+        assert_eq!(game.head(), Coordinate { row: 0, col: 2 });
+        assert_eq!(game.step(None), MoveResult::HitWall);
+    }
+
+    #[test]
+    fn portals_teleport_and_turn_the_head() {
+        static PORTALS: [Portal; 1] = [Portal {
+            a: Coordinate { row: 0, col: 2 },
+            b: Coordinate { row: 3, col: 0 },
+        }];
+        let mut game = Game::with_seed(
+            1,
+            GameConfig {
+                boundary: BoundaryMode::Portals(&PORTALS),
+                ..Default::default()
+            },
+        );
+        game.food = Coordinate { row: 4, col: 4 };
+        game.step(None);
+        game.step(None);
+        assert_eq!(game.step(None), MoveResult::Trivial);
+        assert_eq!(game.head(), Coordinate { row: 3, col: 0 });
+        assert_eq!(game.direction(), Direction::Ost);
+        assert_eq!(game.step(None), MoveResult::Trivial);
+        assert_eq!(game.head(), Coordinate { row: 3, col: 1 });
+    }
+
+    #[test]
+    fn score_counts_food_times_multiplier() {
+        let mut game = Game::with_seed(
+            1,
+            GameConfig {
+                score_multiplier: 3,
+                ..Default::default()
+            },
+        );
+        for row in [1, 0] {
+            game.food = Coordinate { row, col: 2 };
+            game.step(None);
+        }
+        assert_eq!(game.food_eaten(), 2);
+        assert_eq!(game.score(), 6);
+    }
+
+    #[test]
+    fn filling_the_whole_board_wins() {
+        let mut game = Game::with_seed(1, GameConfig::default());
+        // north through a column, then one step east into the next one, the torus is
+        // covered after 24 moves
+        for step in 1..25 {
+            match step % 5 {
+                0 => game.update_direction(ButtonCode::PressedB),
+                1 if step > 1 => game.update_direction(ButtonCode::PressedA),
+                _ => (),
+            }
+            game.food = game.get_new_head_coordinate().unwrap().0;
+            let expected = if step < 24 {
+                MoveResult::Trivial
+            } else {
+                MoveResult::Win
+            };
+            assert_eq!(game.do_move().unwrap(), expected, "step {step}");
+        }
+        assert_eq!(game.len(), 25);
+        assert_eq!(game.food_eaten(), 24);
+        let snapshot = game.get_snapshot();
+        assert!(snapshot
+            .buffer
+            .iter()
+            .flatten()
+            .all(|&cell| cell == CellState::SnakeHead || cell == CellState::SnakeTail));
+    }
+
+    /// A snake filling column 2 of the torus with its head right behind its tail.
+    fn column_snake(tail: TailRule) -> Game {
+        let mut game = Game::with_seed(
+            1,
+            GameConfig {
+                tail,
+                ..Default::default()
+            },
+        );
+        for row in [1, 0, 4, 3] {
+            game.food = Coordinate { row, col: 2 };
+            assert_eq!(game.step(None), MoveResult::Trivial);
+        }
+        game.food = Coordinate { row: 0, col: 0 };
+        assert_eq!(game.len(), 5);
+        game
+    }
+
+    #[test]
+    fn strict_tail_is_bitten() {
+        let mut game = column_snake(TailRule::Strict);
+        assert_eq!(game.step(None), MoveResult::BiteYourself);
+    }
+
+    #[test]
+    fn chased_tail_makes_way() {
+        let mut game = column_snake(TailRule::Chase);
+        for row in [2, 1, 0, 4, 3].repeat(3) {
+            assert_eq!(game.step(None), MoveResult::Trivial);
+            assert_eq!(game.head(), Coordinate { row, col: 2 });
+            assert_eq!(game.len(), 5);
+        }
+        // the rest of the body is still in the way
+        assert_eq!(game.step(Some(ButtonCode::PressedB)), MoveResult::Trivial);
+        assert_eq!(game.step(Some(ButtonCode::PressedB)), MoveResult::Trivial);
+        assert_eq!(
+            game.step(Some(ButtonCode::PressedB)),
+            MoveResult::BiteYourself
+        );
+    }
+
+    static PILLARS: Level = Level {
+        layout: &["..#..", ".....", ".....", ".....", "##.##"],
+        food_to_clear: 2,
+    };
+
+    fn level_config() -> GameConfig {
+        GameConfig {
+            level: Some(&PILLARS),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn running_into_a_wall_ends_the_game() {
+        let mut game = Game::with_seed(1, level_config());
+        game.food = Coordinate { row: 2, col: 0 };
+        assert_eq!(game.step(None), MoveResult::Trivial);
+        assert_eq!(game.step(None), MoveResult::HitWall);
+        assert_eq!(game.head(), Coordinate { row: 1, col: 2 });
+    }
+
+    #[test]
+    fn food_never_lands_on_a_wall() {
+        for seed in 0..200 {
+            let game = Game::with_seed(seed, level_config());
+            assert!(!PILLARS.is_wall(game.food()));
+        }
+    }
+
+    #[test]
+    fn level_is_cleared_after_enough_food() {
+        let mut game = Game::with_seed(1, level_config());
+        game.update_direction(ButtonCode::PressedB);
+        game.food = Coordinate { row: 2, col: 3 };
+        assert_eq!(game.step(None), MoveResult::Trivial);
+        game.food = Coordinate { row: 2, col: 4 };
+        assert_eq!(game.step(None), MoveResult::LevelCleared);
+        assert_eq!(game.food_eaten(), 2);
+    }
+
+    #[test]
+    fn snapshot_shows_the_walls() {
+        let game = Game::with_seed(1, level_config());
+        let snapshot = game.get_snapshot();
+        assert_eq!(snapshot.buffer[2][0], CellState::Wall);
+        assert_eq!(snapshot.buffer[0][4], CellState::Wall);
+        assert_ne!(snapshot.buffer[2][4], CellState::Wall);
+    }
+
+    struct ScriptedRng(Vec<u32>);
+
+    impl Rng for ScriptedRng {
+        fn next_below(&mut self, bound: u32) -> u32 {
+            self.0.remove(0) % bound
+        }
+    }
+
+    #[test]
+    fn food_follows_the_rng() {
+        let mut game: super::Game<5, 5, 25, _> =
+            super::Game::new(ScriptedRng(vec![0, 3]), GameConfig::default());
+        // empty cells are counted column by column
+        assert_eq!(game.food(), Coordinate { row: 0, col: 0 });
+        game.food = Coordinate { row: 1, col: 2 };
+        game.step(None);
+        assert_eq!(game.food(), Coordinate { row: 3, col: 0 });
+    }
+
+    #[test]
+    fn same_seed_same_game() {
+        let mut a = Game::with_seed(1234, GameConfig::default());
+        let mut b = Game::with_seed(1234, GameConfig::default());
+        for tick in 0..50 {
+            let input = (tick % 3 == 0).then_some(ButtonCode::PressedB);
+            assert_eq!(a.step(input), b.step(input));
+            assert_eq!(a.food(), b.food());
+            assert_eq!(a.head(), b.head());
+        }
+    }
+}
@@ -0,0 +1,360 @@
+use core::ops::Range;
+
+use embedded_storage::nor_flash::NorFlash;
+
+use crate::{difficulty::DIFFICULTY_COUNT, menu::GameMode};
+
+// the single-player modes, their scores don't compare so each has its own per difficulty
+const SCORED_MODES: [GameMode; 2] = [GameMode::Endless, GameMode::Campaign];
+pub const SCORE_COUNT: usize = SCORED_MODES.len() * DIFFICULTY_COUNT;
+
+const MAGIC: u16 = 0x5c4a;
+const VERSION: u8 = 1;
+// magic, version, padding, sequence, scores, padding, crc; a power of two so the slots
+// tile any flash page whatever the number of scores
+const RECORD_SIZE: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Record<const N: usize> {
+    sequence: u32,
+    scores: [u32; N],
+}
+
+impl<const N: usize> Default for Record<N> {
+    fn default() -> Self {
+        Record {
+            sequence: 0,
+            scores: [0; N],
+        }
+    }
+}
+
+impl<const N: usize> Record<N> {
+    fn encode(&self) -> [u8; RECORD_SIZE] {
+        let mut bytes = [0; RECORD_SIZE];
+        bytes[0..2].copy_from_slice(&MAGIC.to_le_bytes());
+        bytes[2] = VERSION;
+        bytes[4..8].copy_from_slice(&self.sequence.to_le_bytes());
+        for (chunk, score) in bytes[8..8 + 4 * N].chunks_exact_mut(4).zip(self.scores) {
+            chunk.copy_from_slice(&score.to_le_bytes());
+        }
+        let crc = crc32(&bytes[..RECORD_SIZE - 4]);
+        bytes[RECORD_SIZE - 4..].copy_from_slice(&crc.to_le_bytes());
+        bytes
+    }
+
+    fn decode(bytes: &[u8; RECORD_SIZE]) -> Option<Self> {
+        let word = |at: usize| {
+            u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+        };
+        if u16::from_le_bytes([bytes[0], bytes[1]]) != MAGIC
+            || bytes[2] != VERSION
+            || word(RECORD_SIZE - 4) != crc32(&bytes[..RECORD_SIZE - 4])
+        {
+            return None;
+        }
+        let mut scores = [0; N];
+        for (index, score) in scores.iter_mut().enumerate() {
+            *score = word(8 + 4 * index);
+        }
+        Some(Record {
+            sequence: word(4),
+            scores,
+        })
+    }
+}
+
+/// Position of the best score of `difficulty` in `mode` in the store, `None` for the modes
+/// without high scores.
+pub fn score_index(mode: GameMode, difficulty: usize) -> Option<usize> {
+    let mode = SCORED_MODES.iter().position(|&scored| scored == mode)?;
+    (difficulty < DIFFICULTY_COUNT).then_some(mode * DIFFICULTY_COUNT + difficulty)
+}
+
+/// The best score per mode and difficulty, kept as a log of records in a range of flash
+/// pages.
+///
+/// Every update is appended to the next free slot and a page is only erased once the
+/// log wraps around into it, so all pages wear evenly and the previous record is still
+/// there if the power fails in the middle of an update. `N` is the number of scores,
+/// one per `score_index` unless told otherwise.
+pub struct HighScoreStore<F, const N: usize = SCORE_COUNT> {
+    flash: F,
+    range: Range<u32>,
+    record: Record<N>,
+    next: u32,
+}
+
+impl<F: NorFlash, const N: usize> HighScoreStore<F, N> {
+    /// Loads the latest valid record from `range`, which has to span at least two
+    /// erasable pages.
+    pub fn new(mut flash: F, range: Range<u32>) -> Result<Self, F::Error> {
+        const { assert!(RECORD_SIZE.is_power_of_two() && 8 + 4 * N + 4 <= RECORD_SIZE) };
+        let erase_size = F::ERASE_SIZE as u32;
+        assert!(range.start % erase_size == 0 && range.end % erase_size == 0);
+        assert!(range.end - range.start >= 2 * erase_size);
+        assert!(F::ERASE_SIZE % RECORD_SIZE == 0 && RECORD_SIZE % F::WRITE_SIZE == 0);
+
+        let mut latest: Option<(u32, Record<N>)> = None;
+        for offset in (range.start..range.end).step_by(RECORD_SIZE) {
+            let mut bytes = [0; RECORD_SIZE];
+            flash.read(offset, &mut bytes)?;
+            if let Some(record) = Record::decode(&bytes) {
+                if latest.map_or(true, |(_, latest)| record.sequence > latest.sequence) {
+                    latest = Some((offset, record));
+                }
+            }
+        }
+        let (record, next) = match latest {
+            Some((offset, record)) => (record, offset + RECORD_SIZE as u32),
+            None => (Record::default(), range.start),
+        };
+        Ok(HighScoreStore {
+            flash,
+            range,
+            record,
+            next,
+        })
+    }
+
+    pub fn get(&self, index: usize) -> u32 {
+        self.record.scores[index]
+    }
+
+    /// Stores `score` if it beats the best one at `index`, returns whether it did.
+    pub fn submit(&mut self, index: usize, score: u32) -> Result<bool, F::Error> {
+        if score <= self.record.scores[index] {
+            return Ok(false);
+        }
+        let mut record = self.record;
+        record.sequence = record.sequence.wrapping_add(1);
+        record.scores[index] = score;
+        self.append(&record)?;
+        self.record = record;
+        Ok(true)
+    }
+
+    pub fn release(self) -> F {
+        self.flash
+    }
+
+    fn append(&mut self, record: &Record<N>) -> Result<(), F::Error> {
+        loop {
+            if self.next >= self.range.end {
+                self.next = self.range.start;
+            }
+            let slot = self.next;
+            self.next += RECORD_SIZE as u32;
+            if slot % F::ERASE_SIZE as u32 == 0 {
+                self.flash.erase(slot, slot + F::ERASE_SIZE as u32)?;
+            }
+            // a torn write from a power loss leaves a slot we can't write over
+            let mut bytes = [0; RECORD_SIZE];
+            self.flash.read(slot, &mut bytes)?;
+            if bytes.iter().all(|&byte| byte == 0xff) {
+                return self.flash.write(slot, &record.encode());
+            }
+        }
+    }
+}
+
+/// CRC-32 as used by zlib and Ethernet.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_storage::nor_flash::{ErrorType, NorFlashErrorKind, ReadNorFlash};
+
+    const PAGE: usize = 4096;
+
+    /// RAM backed flash that, like NOR flash, can only clear bits on write.
+    struct MockFlash {
+        bytes: Vec<u8>,
+        erases: Vec<usize>,
+    }
+
+    impl MockFlash {
+        fn new(pages: usize) -> Self {
+            MockFlash {
+                bytes: vec![0xff; pages * PAGE],
+                erases: vec![0; pages],
+            }
+        }
+    }
+
+    impl ErrorType for MockFlash {
+        type Error = NorFlashErrorKind;
+    }
+
+    impl ReadNorFlash for MockFlash {
+        const READ_SIZE: usize = 1;
+
+        fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+            let offset = offset as usize;
+            let source = self
+                .bytes
+                .get(offset..offset + bytes.len())
+                .ok_or(NorFlashErrorKind::OutOfBounds)?;
+            bytes.copy_from_slice(source);
+            Ok(())
+        }
+
+        fn capacity(&self) -> usize {
+            self.bytes.len()
+        }
+    }
+
+    impl NorFlash for MockFlash {
+        const WRITE_SIZE: usize = 4;
+        const ERASE_SIZE: usize = PAGE;
+
+        fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+            let (from, to) = (from as usize, to as usize);
+            if from % PAGE != 0 || to % PAGE != 0 {
+                return Err(NorFlashErrorKind::NotAligned);
+            }
+            self.bytes[from..to].fill(0xff);
+            for page in from / PAGE..to / PAGE {
+                self.erases[page] += 1;
+            }
+            Ok(())
+        }
+
+        fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+            let offset = offset as usize;
+            if offset % Self::WRITE_SIZE != 0 || bytes.len() % Self::WRITE_SIZE != 0 {
+                return Err(NorFlashErrorKind::NotAligned);
+            }
+            for (target, byte) in self.bytes[offset..offset + bytes.len()]
+                .iter_mut()
+                .zip(bytes)
+            {
+                *target &= byte;
+            }
+            Ok(())
+        }
+    }
+
+    type Store = HighScoreStore<MockFlash>;
+
+    fn range(pages: usize) -> Range<u32> {
+        0..(pages * PAGE) as u32
+    }
+
+    #[test]
+    fn crc_matches_the_reference() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn erased_flash_has_no_scores() {
+        let store = Store::new(MockFlash::new(2), range(2)).unwrap();
+        assert!((0..SCORE_COUNT).all(|index| store.get(index) == 0));
+    }
+
+    #[test]
+    fn scores_survive_a_reload() {
+        let mut store = Store::new(MockFlash::new(2), range(2)).unwrap();
+        assert!(store.submit(1, 12).unwrap());
+        assert!(store.submit(4, 30).unwrap());
+        assert!(!store.submit(1, 10).unwrap());
+        let store = Store::new(store.release(), range(2)).unwrap();
+        assert_eq!(store.get(1), 12);
+        assert_eq!(store.get(4), 30);
+        assert_eq!(store.get(0), 0);
+    }
+
+    #[test]
+    fn modes_keep_scores_of_their_own() {
+        let endless = score_index(GameMode::Endless, 2).unwrap();
+        let campaign = score_index(GameMode::Campaign, 2).unwrap();
+        assert_ne!(endless, campaign);
+        assert_eq!(score_index(GameMode::Versus, 2), None);
+        assert_eq!(score_index(GameMode::HotSeat, 2), None);
+        assert_eq!(score_index(GameMode::Campaign, DIFFICULTY_COUNT), None);
+        assert_eq!(
+            score_index(GameMode::Campaign, DIFFICULTY_COUNT - 1),
+            Some(SCORE_COUNT - 1)
+        );
+
+        let mut store = Store::new(MockFlash::new(2), range(2)).unwrap();
+        assert!(store.submit(endless, 40).unwrap());
+        assert!(store.submit(campaign, 12).unwrap());
+        let store = Store::new(store.release(), range(2)).unwrap();
+        assert_eq!((store.get(endless), store.get(campaign)), (40, 12));
+    }
+
+    #[test]
+    fn pages_wear_evenly() {
+        let mut store = Store::new(MockFlash::new(3), range(3)).unwrap();
+        for score in 1..=1000 {
+            store.submit(2, score).unwrap();
+        }
+        let flash = store.release();
+        let (min, max) = (
+            flash.erases.iter().min().unwrap(),
+            flash.erases.iter().max().unwrap(),
+        );
+        assert!(max - min <= 1, "{:?}", flash.erases);
+        let store = Store::new(flash, range(3)).unwrap();
+        assert_eq!(store.get(2), 1000);
+    }
+
+    #[test]
+    fn corrupted_record_falls_back_to_the_previous_one() {
+        let mut store = Store::new(MockFlash::new(2), range(2)).unwrap();
+        store.submit(0, 5).unwrap();
+        store.submit(0, 7).unwrap();
+        let mut flash = store.release();
+        flash.bytes[RECORD_SIZE + 8] ^= 0x01;
+        let mut store = Store::new(flash, range(2)).unwrap();
+        assert_eq!(store.get(0), 5);
+        // the damaged slot is skipped rather than written over
+        store.submit(0, 6).unwrap();
+        let store = Store::new(store.release(), range(2)).unwrap();
+        assert_eq!(store.get(0), 6);
+    }
+
+    #[test]
+    fn any_number_of_scores_fits_a_slot() {
+        let mut store = HighScoreStore::<_, 3>::new(MockFlash::new(2), range(2)).unwrap();
+        store.submit(2, 8).unwrap();
+        let store = HighScoreStore::<_, 3>::new(store.release(), range(2)).unwrap();
+        assert_eq!(store.get(2), 8);
+
+        let mut store = HighScoreStore::<_, 13>::new(MockFlash::new(2), range(2)).unwrap();
+        for score in 1..=200 {
+            store.submit(12, score).unwrap();
+        }
+        let store = HighScoreStore::<_, 13>::new(store.release(), range(2)).unwrap();
+        assert_eq!(store.get(12), 200);
+    }
+
+    #[test]
+    fn records_of_another_version_are_ignored() {
+        let mut flash = MockFlash::new(2);
+        let mut bytes = Record {
+            sequence: 1,
+            scores: [9; SCORE_COUNT],
+        }
+        .encode();
+        bytes[2] = VERSION + 1;
+        flash.write(0, &bytes).unwrap();
+        let store = Store::new(flash, range(2)).unwrap();
+        assert_eq!(store.get(0), 0);
+    }
+}
@@ -0,0 +1,217 @@
+use core::{cmp::Reverse, fmt};
+
+use heapless::Vec;
+
+use crate::{
+    draw::Canvas,
+    game::Game,
+    input::ButtonCode,
+    rng::Rng,
+    snapshot::{CellState, Snapshot},
+};
+
+pub const MIN_PLAYERS: usize = 2;
+pub const MAX_PLAYERS: usize = 4;
+
+/// How one player's game went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundResult {
+    /// Numbered from 1, the way the players are called on the display.
+    pub player: usize,
+    pub length: usize,
+    pub food_eaten: u32,
+    pub score: u32,
+}
+
+/// Players taking turns on the same difficulty, one game each, the results are kept
+/// until the last one has played.
+pub struct HotSeat {
+    players: usize,
+    results: Vec<RoundResult, MAX_PLAYERS>,
+}
+
+impl HotSeat {
+    /// `players` is clamped to `MIN_PLAYERS..=MAX_PLAYERS`.
+    pub fn new(players: usize) -> Self {
+        HotSeat {
+            players: players.clamp(MIN_PLAYERS, MAX_PLAYERS),
+            results: Vec::new(),
+        }
+    }
+
+    pub fn players(&self) -> usize {
+        self.players
+    }
+
+    /// Number of the player whose turn it is, `None` once everyone has played.
+    pub fn current_player(&self) -> Option<usize> {
+        (!self.is_finished()).then_some(self.results.len() + 1)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.results.len() == self.players
+    }
+
+    /// Keeps the result of the current player's finished `game`, nothing happens once
+    /// everyone has played.
+    pub fn record<const NCOLS: usize, const NROWS: usize, const NCELLS: usize, R: Rng>(
+        &mut self,
+        game: &Game<NCOLS, NROWS, NCELLS, R>,
+    ) {
+        if let Some(player) = self.current_player() {
+            // there is room as long as somebody is left to play
+            let _ = self.results.push(RoundResult {
+                player,
+                length: game.len(),
+                food_eaten: game.food_eaten(),
+                score: game.score(),
+            });
+        }
+    }
+
+    pub fn results(&self) -> &[RoundResult] {
+        &self.results
+    }
+
+    /// The results from the best score to the worst. Equal scores go by food eaten,
+    /// then by the order of play.
+    pub fn ranking(&self) -> Vec<RoundResult, MAX_PLAYERS> {
+        let mut ranking = self.results.clone();
+        ranking.sort_unstable_by_key(|result| {
+            (
+                Reverse(result.score),
+                Reverse(result.food_eaten),
+                result.player,
+            )
+        });
+        ranking
+    }
+
+    /// The ranking as one line for a marquee, e.g. `1.P2 12 2.P1 8`.
+    pub fn write_ranking(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        for (place, result) in self.ranking().iter().enumerate() {
+            if place > 0 {
+                out.write_char(' ')?;
+            }
+            write!(out, "{}.P{} {}", place + 1, result.player, result.score)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct PlayerCountSelector {
+    players: usize,
+}
+
+impl PlayerCountSelector {
+    pub fn new() -> Self {
+        Self::with_players(MIN_PLAYERS)
+    }
+
+    /// A selector that offers `players` first, clamped like in `HotSeat::new`.
+    pub fn with_players(players: usize) -> Self {
+        PlayerCountSelector {
+            players: players.clamp(MIN_PLAYERS, MAX_PLAYERS),
+        }
+    }
+
+    pub fn is_choice_made(&mut self, input: ButtonCode) -> Option<()> {
+        match input {
+            ButtonCode::PressedA => {
+                self.players = if self.players == MAX_PLAYERS {
+                    MIN_PLAYERS
+                } else {
+                    self.players + 1
+                };
+                None
+            }
+            ButtonCode::PressedB => Some(()),
+            _ => None,
+        }
+    }
+
+    pub fn get_players(&self) -> usize {
+        self.players
+    }
+
+    /// The number of players as a blinking digit.
+    pub fn get_snapshot(&self) -> Snapshot<5, 5> {
+        let digit = char::from_digit(self.players as u32, 10).unwrap_or('?');
+        Canvas::new(CellState::AnimationBlinking)
+            .glyph(digit)
+            .finish()
+    }
+}
+
+impl Default for PlayerCountSelector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        boundary::BoundaryMode,
+        game::{GameConfig, MoveResult},
+    };
+
+    fn hot_seat(scores: &[(u32, u32)]) -> HotSeat {
+        let mut hot_seat = HotSeat::new(scores.len());
+        for (index, &(score, food_eaten)) in scores.iter().enumerate() {
+            let _ = hot_seat.results.push(RoundResult {
+                player: index + 1,
+                length: food_eaten as usize + 1,
+                food_eaten,
+                score,
+            });
+        }
+        hot_seat
+    }
+
+    #[test]
+    fn players_take_turns() {
+        let mut hot_seat = HotSeat::new(2);
+        assert_eq!(hot_seat.current_player(), Some(1));
+        let config = GameConfig {
+            boundary: BoundaryMode::Walls,
+            ..Default::default()
+        };
+        let mut game: Game<5, 5, 25> = Game::with_seed(3, config);
+        // straight into the edge
+        while game.step(None) == MoveResult::Trivial {}
+        hot_seat.record(&game);
+        assert_eq!(hot_seat.current_player(), Some(2));
+        hot_seat.record(&game);
+        assert!(hot_seat.is_finished());
+        assert_eq!(hot_seat.current_player(), None);
+        hot_seat.record(&game);
+        assert_eq!(hot_seat.results().len(), 2);
+        assert_eq!(hot_seat.results()[1].player, 2);
+        assert_eq!(hot_seat.results()[1].length, game.len());
+        assert_eq!(HotSeat::new(9).players(), MAX_PLAYERS);
+    }
+
+    #[test]
+    fn ranking_goes_by_score_then_food_then_order() {
+        let hot_seat = hot_seat(&[(4, 2), (12, 6), (4, 4), (4, 2)]);
+        let players: std::vec::Vec<_> = hot_seat.ranking().iter().map(|r| r.player).collect();
+        assert_eq!(players, [2, 3, 1, 4]);
+
+        let mut text = std::string::String::new();
+        hot_seat.write_ranking(&mut text).unwrap();
+        assert_eq!(text, "1.P2 12 2.P3 4 3.P1 4 4.P4 4");
+    }
+
+    #[test]
+    fn selector_counts_two_to_four() {
+        let mut selector = PlayerCountSelector::new();
+        assert_eq!(selector.get_players(), 2);
+        for players in [3, 4, 2] {
+            assert!(selector.is_choice_made(ButtonCode::PressedA).is_none());
+            assert_eq!(selector.get_players(), players);
+        }
+        assert!(selector.is_choice_made(ButtonCode::PressedB).is_some());
+    }
+}
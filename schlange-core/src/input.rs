@@ -0,0 +1,319 @@
+use heapless::Deque;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonCode {
+    PressedA,
+    PressedB,
+    LongPressedA,
+    LongPressedB,
+    DoublePressedA,
+    DoublePressedB,
+    PressedAB,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Button {
+    A,
+    B,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    Pressed,
+    Released,
+}
+
+/// Time windows of the gesture classifier in milliseconds, zero disables a gesture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GestureWindows {
+    /// Both buttons going down within this window form a chord.
+    pub chord_ms: u64,
+    /// Holding a button at least this long is a long press.
+    pub long_press_ms: u64,
+    /// Pressing again within this window after a release is a double press.
+    pub double_press_ms: u64,
+}
+
+impl Default for GestureWindows {
+    fn default() -> Self {
+        GestureWindows {
+            chord_ms: 50,
+            long_press_ms: 500,
+            double_press_ms: 300,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum KeyState {
+    Up { released_at: Option<u64> },
+    // within the chord window, nothing has been reported yet
+    Down { since: u64, double: bool },
+    // the press has been reported, a long press may follow
+    Pressed { since: u64 },
+    // nothing left to report, wait for the release
+    Held,
+}
+
+/// Turns debounced edges of both buttons into `ButtonCode`s.
+///
+/// A press is reported as soon as the chord window is over, or on release if that
+/// comes first. Holding the button on reports a long press later, and a press soon
+/// after a release is reported as a double press instead of a plain one.
+pub struct GestureClassifier {
+    windows: GestureWindows,
+    keys: [KeyState; 2],
+    codes: Deque<ButtonCode, 4>,
+}
+
+impl GestureClassifier {
+    pub fn new(windows: GestureWindows) -> Self {
+        GestureClassifier {
+            windows,
+            keys: [KeyState::Up { released_at: None }; 2],
+            codes: Deque::new(),
+        }
+    }
+
+    pub fn feed(&mut self, button: Button, edge: Edge, at_ms: u64) {
+        self.expire(at_ms);
+        let (this, other) = match button {
+            Button::A => (0, 1),
+            Button::B => (1, 0),
+        };
+        match (edge, self.keys[this]) {
+            (Edge::Pressed, KeyState::Up { released_at }) => {
+                // the other key is still in its chord window
+                if let KeyState::Down { .. } = self.keys[other] {
+                    self.keys = [KeyState::Held; 2];
+                    self.emit(ButtonCode::PressedAB);
+                    return;
+                }
+                let double = released_at.is_some_and(|released_at| {
+                    within(released_at, at_ms, self.windows.double_press_ms)
+                });
+                self.keys[this] = KeyState::Down {
+                    since: at_ms,
+                    double,
+                };
+                self.expire(at_ms);
+            }
+            (Edge::Released, KeyState::Down { double, .. }) => {
+                self.emit(if double {
+                    double_press(button)
+                } else {
+                    press(button)
+                });
+                // a third press is a new start
+                self.keys[this] = KeyState::Up {
+                    released_at: (!double).then_some(at_ms),
+                };
+            }
+            (Edge::Released, KeyState::Pressed { .. }) => {
+                self.keys[this] = KeyState::Up {
+                    released_at: Some(at_ms),
+                };
+            }
+            (Edge::Released, KeyState::Held) => {
+                self.keys[this] = KeyState::Up { released_at: None };
+            }
+            // a repeated edge means we missed one, keep the current state
+            _ => (),
+        }
+    }
+
+    pub fn poll(&mut self, now_ms: u64) -> Option<ButtonCode> {
+        self.expire(now_ms);
+        self.codes.pop_front()
+    }
+
+    /// The next moment `poll` may report something without a new edge.
+    pub fn next_deadline(&self) -> Option<u64> {
+        self.keys
+            .iter()
+            .filter_map(|key| match *key {
+                KeyState::Down { since, .. } => Some(since + self.windows.chord_ms),
+                KeyState::Pressed { since } if self.windows.long_press_ms > 0 => {
+                    Some(since + self.windows.long_press_ms)
+                }
+                _ => None,
+            })
+            .min()
+    }
+
+    fn expire(&mut self, now_ms: u64) {
+        for (key, button) in [(0, Button::A), (1, Button::B)] {
+            if let KeyState::Down { since, double } = self.keys[key] {
+                if now_ms >= since + self.windows.chord_ms {
+                    if double {
+                        self.keys[key] = KeyState::Held;
+                        self.emit(double_press(button));
+                    } else {
+                        self.keys[key] = KeyState::Pressed { since };
+                        self.emit(press(button));
+                    }
+                }
+            }
+            if let KeyState::Pressed { since } = self.keys[key] {
+                let long_press_ms = self.windows.long_press_ms;
+                if long_press_ms > 0 && now_ms >= since + long_press_ms {
+                    self.keys[key] = KeyState::Held;
+                    self.emit(long_press(button));
+                }
+            }
+        }
+    }
+
+    fn emit(&mut self, code: ButtonCode) {
+        // nobody polls, the oldest gesture is the least relevant one
+        if self.codes.is_full() {
+            self.codes.pop_front();
+        }
+        let _ = self.codes.push_back(code);
+    }
+}
+
+fn within(since: u64, at: u64, window: u64) -> bool {
+    window > 0 && at.saturating_sub(since) <= window
+}
+
+fn press(button: Button) -> ButtonCode {
+    match button {
+        Button::A => ButtonCode::PressedA,
+        Button::B => ButtonCode::PressedB,
+    }
+}
+
+fn long_press(button: Button) -> ButtonCode {
+    match button {
+        Button::A => ButtonCode::LongPressedA,
+        Button::B => ButtonCode::LongPressedB,
+    }
+}
+
+fn double_press(button: Button) -> ButtonCode {
+    match button {
+        Button::A => ButtonCode::DoublePressedA,
+        Button::B => ButtonCode::DoublePressedB,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(windows: GestureWindows, trace: &[(u64, Button, Edge)]) -> Vec<(u64, ButtonCode)> {
+        let mut classifier = GestureClassifier::new(windows);
+        let mut codes = Vec::new();
+        let end = trace.last().map_or(0, |&(at, _, _)| at) + 1000;
+        let mut trace = trace.iter().peekable();
+        for now in 0..end {
+            while let Some(&&(at, button, edge)) = trace.peek() {
+                if at > now {
+                    break;
+                }
+                classifier.feed(button, edge, at);
+                trace.next();
+            }
+            while let Some(code) = classifier.poll(now) {
+                codes.push((now, code));
+            }
+        }
+        codes
+    }
+
+    use Button::{A, B};
+    use Edge::{Pressed, Released};
+
+    #[test]
+    fn press_is_reported_once_the_chord_window_is_over() {
+        let codes = classify(
+            GestureWindows::default(),
+            &[(100, A, Pressed), (180, A, Released)],
+        );
+        assert_eq!(codes, [(150, ButtonCode::PressedA)]);
+        // a quicker release doesn't wait for the window
+        let codes = classify(
+            GestureWindows::default(),
+            &[(100, A, Pressed), (120, A, Released)],
+        );
+        assert_eq!(codes, [(120, ButtonCode::PressedA)]);
+    }
+
+    #[test]
+    fn long_press_follows_the_press() {
+        let codes = classify(
+            GestureWindows::default(),
+            &[(100, B, Pressed), (1500, B, Released)],
+        );
+        assert_eq!(
+            codes,
+            [(150, ButtonCode::PressedB), (600, ButtonCode::LongPressedB)]
+        );
+    }
+
+    #[test]
+    fn double_press_replaces_the_second_press() {
+        let codes = classify(
+            GestureWindows::default(),
+            &[
+                (100, A, Pressed),
+                (200, A, Released),
+                (300, A, Pressed),
+                (400, A, Released),
+                (500, A, Pressed),
+                (600, A, Released),
+            ],
+        );
+        assert_eq!(
+            codes,
+            [
+                (150, ButtonCode::PressedA),
+                (350, ButtonCode::DoublePressedA),
+                (550, ButtonCode::PressedA),
+            ]
+        );
+    }
+
+    #[test]
+    fn chord_swallows_the_single_presses() {
+        let codes = classify(
+            GestureWindows::default(),
+            &[
+                (100, A, Pressed),
+                (130, B, Pressed),
+                (400, B, Released),
+                (420, A, Released),
+            ],
+        );
+        assert_eq!(codes, [(130, ButtonCode::PressedAB)]);
+    }
+
+    #[test]
+    fn late_second_button_is_no_chord() {
+        let codes = classify(
+            GestureWindows::default(),
+            &[
+                (100, A, Pressed),
+                (200, B, Pressed),
+                (250, A, Released),
+                (260, B, Released),
+            ],
+        );
+        assert_eq!(
+            codes,
+            [(150, ButtonCode::PressedA), (250, ButtonCode::PressedB)]
+        );
+    }
+
+    #[test]
+    fn without_a_chord_window_the_press_is_reported_right_away() {
+        let windows = GestureWindows {
+            chord_ms: 0,
+            long_press_ms: 0,
+            double_press_ms: 0,
+        };
+        let codes = classify(windows, &[(100, B, Pressed), (400, B, Released)]);
+        assert_eq!(codes, [(100, ButtonCode::PressedB)]);
+    }
+}
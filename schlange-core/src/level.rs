@@ -0,0 +1,138 @@
+use crate::game::Coordinate;
+
+/// A board with walls in it, the snake dies on a wall just like on its own body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Level {
+    /// One line per row, `#` marks a wall, cells beyond the layout are free.
+    pub layout: &'static [&'static str],
+    /// Food to eat before the next level starts.
+    pub food_to_clear: u32,
+}
+
+impl Level {
+    pub fn is_wall(&self, coordinate: Coordinate) -> bool {
+        self.layout
+            .get(coordinate.row as usize)
+            .and_then(|line| line.as_bytes().get(coordinate.col as usize))
+            == Some(&b'#')
+    }
+}
+
+/// The levels of the 5x5 board, from the empty one to the most cluttered. They all
+/// keep the column of the snake's start and the portals free.
+#[rustfmt::skip]
+pub static CAMPAIGN: [Level; 4] = [
+    Level {
+        layout: &[],
+        food_to_clear: 5,
+    },
+    Level {
+        layout: &[
+            "#...#",
+            ".....",
+            ".....",
+            ".....",
+            "#...#",
+        ],
+        food_to_clear: 5,
+    },
+    Level {
+        layout: &[
+            ".....",
+            ".#.#.",
+            ".#.#.",
+            ".#.#.",
+            ".....",
+        ],
+        food_to_clear: 6,
+    },
+    Level {
+        layout: &[
+            ".#.#.",
+            "#...#",
+            ".....",
+            "#...#",
+            ".#.#.",
+        ],
+        food_to_clear: 8,
+    },
+];
+
+/// Plays a list of levels one after the other.
+pub struct Campaign {
+    levels: &'static [Level],
+    current: usize,
+}
+
+impl Campaign {
+    /// `levels` must not be empty.
+    pub fn new(levels: &'static [Level]) -> Self {
+        assert!(!levels.is_empty());
+        Campaign { levels, current: 0 }
+    }
+
+    pub fn level(&self) -> &'static Level {
+        &self.levels[self.current]
+    }
+
+    /// Position of the current level, counted from zero.
+    pub fn get_index(&self) -> usize {
+        self.current
+    }
+
+    /// Moves on to the next level, returns false once the last one was cleared.
+    pub fn advance(&mut self) -> bool {
+        if self.current + 1 < self.levels.len() {
+            self.current += 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl Default for Campaign {
+    fn default() -> Self {
+        Self::new(&CAMPAIGN)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary::CROSS_PORTALS;
+
+    #[test]
+    fn layout_marks_the_walls() {
+        let level = &CAMPAIGN[1];
+        assert!(level.is_wall(Coordinate { row: 0, col: 0 }));
+        assert!(level.is_wall(Coordinate { row: 4, col: 4 }));
+        assert!(!level.is_wall(Coordinate { row: 0, col: 1 }));
+        // outside of the layout
+        assert!(!level.is_wall(Coordinate { row: 7, col: 0 }));
+        assert!(!level.is_wall(Coordinate { row: 0, col: 9 }));
+    }
+
+    #[test]
+    fn campaign_levels_leave_start_and_portals_free() {
+        for level in CAMPAIGN.iter() {
+            for row in 0..5 {
+                assert!(!level.is_wall(Coordinate { row, col: 2 }));
+            }
+            for portal in CROSS_PORTALS.iter() {
+                assert!(!level.is_wall(portal.a) && !level.is_wall(portal.b));
+            }
+        }
+    }
+
+    #[test]
+    fn campaign_ends_after_the_last_level() {
+        let mut campaign = Campaign::default();
+        for index in 1..CAMPAIGN.len() {
+            assert!(campaign.advance());
+            assert_eq!(campaign.get_index(), index);
+        }
+        assert!(!campaign.advance());
+        assert_eq!(campaign.level(), &CAMPAIGN[CAMPAIGN.len() - 1]);
+    }
+}
@@ -0,0 +1,23 @@
+#![cfg_attr(not(test), no_std)]
+
+pub mod app;
+pub mod boundary;
+pub mod clock;
+pub mod controller;
+pub mod difficulty;
+pub mod draw;
+pub mod font;
+pub mod food;
+pub mod game;
+pub mod highscore;
+pub mod hotseat;
+pub mod input;
+pub mod level;
+pub mod marquee;
+pub mod menu;
+pub mod rb;
+pub mod replay;
+pub mod rng;
+pub mod snapshot;
+pub mod speed;
+pub mod versus;
@@ -0,0 +1,147 @@
+use crate::{
+    font::{glyph, GLYPH_HEIGHT},
+    snapshot::{CellState, Snapshot},
+};
+
+/// Scrolls a text from the right edge of the display to the left one, one column per frame.
+/// Characters missing from the font are skipped.
+pub struct Marquee<'a> {
+    text: &'a str,
+    offset: usize,
+    brightness: u16,
+    column_delay_ms: u64,
+}
+
+impl<'a> Marquee<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Marquee {
+            text,
+            offset: 1,
+            brightness: 1000,
+            column_delay_ms: 150,
+        }
+    }
+
+    /// Brightness of the lit pixels, from 0 to 1000.
+    pub fn with_brightness(mut self, brightness: u16) -> Self {
+        self.brightness = brightness.min(1000);
+        self
+    }
+
+    /// How long every column step stays on the display.
+    pub fn with_column_delay_ms(mut self, column_delay_ms: u64) -> Self {
+        self.column_delay_ms = column_delay_ms;
+        self
+    }
+
+    /// The frames together with their delays, the way animations are played back.
+    pub fn frames(self) -> impl Iterator<Item = (Snapshot<5, 5>, u64)> + 'a {
+        let delay = self.column_delay_ms;
+        self.map(move |snapshot| (snapshot, delay))
+    }
+
+    fn width(&self) -> usize {
+        self.glyphs()
+            .map(|columns| columns.len() + 1)
+            .sum::<usize>()
+            .saturating_sub(1)
+    }
+
+    fn glyphs(&self) -> impl Iterator<Item = &'static [u8]> + 'a {
+        self.text.chars().filter_map(glyph)
+    }
+
+    /// The pixels of the `index`th column of the rendered text, glyphs are one column apart.
+    fn column(&self, index: usize) -> u8 {
+        let mut start = 0;
+        for columns in self.glyphs() {
+            if index < start {
+                // the gap in front of this glyph
+                break;
+            }
+            if index < start + columns.len() {
+                return columns[index - start];
+            }
+            start += columns.len() + 1;
+        }
+        0
+    }
+}
+
+impl<'a> Iterator for Marquee<'a> {
+    type Item = Snapshot<5, 5>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset > self.width() + 4 {
+            return None;
+        }
+        let mut snapshot = Snapshot::new();
+        for (col, cells) in snapshot.buffer.iter_mut().enumerate() {
+            // the text starts right behind the right edge of the display
+            let Some(index) = (self.offset + col).checked_sub(5) else {
+                continue;
+            };
+            let pixels = self.column(index);
+            for (row, cell) in cells.iter_mut().enumerate().take(GLYPH_HEIGHT) {
+                if pixels & (1 << row) != 0 {
+                    *cell = CellState::AnimationStatic(self.brightness);
+                }
+            }
+        }
+        self.offset += 1;
+        Some(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lit_columns(snapshot: &Snapshot<5, 5>) -> Vec<usize> {
+        (0..5)
+            .filter(|&col| snapshot.buffer[col].iter().any(|&c| c != CellState::Empty))
+            .collect()
+    }
+
+    #[test]
+    fn text_enters_from_the_right_and_leaves_on_the_left() {
+        let frames: Vec<_> = Marquee::new("1").collect();
+        // 3 columns of the glyph pass all 5 columns of the display
+        assert_eq!(frames.len(), 7);
+        assert_eq!(lit_columns(&frames[0]), [4]);
+        assert_eq!(lit_columns(&frames[2]), [2, 3, 4]);
+        assert_eq!(lit_columns(&frames[6]), [0]);
+    }
+
+    #[test]
+    fn glyphs_are_one_column_apart() {
+        let frames: Vec<_> = Marquee::new("11").collect();
+        assert_eq!(frames.len(), 11);
+        assert_eq!(lit_columns(&frames[4]), [0, 1, 2, 4]);
+    }
+
+    #[test]
+    fn renders_the_glyph_pixels() {
+        let frame = Marquee::new("7").nth(2).unwrap();
+        assert_eq!(frame.buffer[2][0], CellState::AnimationStatic(1000));
+        assert_eq!(frame.buffer[2][1], CellState::Empty);
+        assert_eq!(frame.buffer[3][4], CellState::AnimationStatic(1000));
+    }
+
+    #[test]
+    fn frames_carry_brightness_and_delay() {
+        let (frame, delay) = Marquee::new("A")
+            .with_brightness(300)
+            .with_column_delay_ms(80)
+            .frames()
+            .next()
+            .unwrap();
+        assert_eq!(delay, 80);
+        assert_eq!(frame.buffer[4][1], CellState::AnimationStatic(300));
+    }
+
+    #[test]
+    fn unknown_characters_are_skipped() {
+        assert_eq!(Marquee::new("~1~").count(), Marquee::new("1").count());
+    }
+}
@@ -0,0 +1,427 @@
+use heapless::Vec;
+
+use crate::{
+    boundary::{BoundaryMode, BoundarySelector},
+    difficulty::{DifficultySelector, PROFILES},
+    draw::Canvas,
+    hotseat::{PlayerCountSelector, MIN_PLAYERS},
+    input::ButtonCode,
+    snapshot::{CellState, Snapshot},
+};
+
+// pages open at the same time, the main menu counts as one
+const MENU_DEPTH: usize = 4;
+
+pub const BRIGHTNESS_LEVELS: u8 = 5;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameMode {
+    /// One player on a board without levels, until the snake dies or fills it.
+    #[default]
+    Endless,
+    /// One player through the levels.
+    Campaign,
+    /// Two players on one board.
+    Versus,
+    /// Players taking turns, ranked by score.
+    HotSeat,
+}
+
+/// Everything the menu configures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Settings {
+    pub mode: GameMode,
+    /// Players of a hot-seat game.
+    pub players: usize,
+    /// Position of the profile in `PROFILES`.
+    pub difficulty: usize,
+    pub boundary: BoundaryMode,
+    /// From 1 for the dimmest to `BRIGHTNESS_LEVELS`.
+    pub brightness: u8,
+    /// Beeps from the speaker when a snake eats and when it crashes.
+    pub sound: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        let difficulty = DifficultySelector::new().get_index();
+        Settings {
+            mode: Default::default(),
+            players: MIN_PLAYERS,
+            difficulty,
+            boundary: PROFILES[difficulty].boundary,
+            brightness: BRIGHTNESS_LEVELS,
+            sound: true,
+        }
+    }
+}
+
+/// A value of `Settings` that can be edited from the menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    Mode,
+    Players,
+    /// Picking a difficulty also picks the boundary mode of its profile.
+    Difficulty,
+    Boundary,
+    Brightness,
+    Sound,
+}
+
+impl Setting {
+    /// The letter the setting is listed with.
+    pub fn icon(self) -> char {
+        match self {
+            Setting::Mode => 'M',
+            Setting::Players => 'P',
+            Setting::Difficulty => 'D',
+            Setting::Boundary => 'B',
+            Setting::Brightness => 'L',
+            Setting::Sound => 'S',
+        }
+    }
+
+    /// `settings` with this setting moved on to its next value, after the last one
+    /// comes the first one again.
+    pub fn next(self, mut settings: Settings) -> Settings {
+        match self {
+            Setting::Mode => {
+                settings.mode = match settings.mode {
+                    GameMode::Endless => GameMode::Campaign,
+                    GameMode::Campaign => GameMode::Versus,
+                    GameMode::Versus => GameMode::HotSeat,
+                    GameMode::HotSeat => GameMode::Endless,
+                }
+            }
+            Setting::Players => {
+                let mut selector = PlayerCountSelector::with_players(settings.players);
+                selector.is_choice_made(ButtonCode::PressedA);
+                settings.players = selector.get_players();
+            }
+            Setting::Difficulty => {
+                let mut selector = DifficultySelector::with_index(settings.difficulty);
+                selector.is_choice_made(ButtonCode::PressedA);
+                settings.difficulty = selector.get_index();
+                settings.boundary = selector.get_profile().boundary;
+            }
+            Setting::Boundary => {
+                let mut selector = BoundarySelector::with_boundary_mode(settings.boundary);
+                selector.is_choice_made(ButtonCode::PressedA);
+                settings.boundary = selector.get_boundary_mode();
+            }
+            Setting::Brightness => {
+                settings.brightness = settings.brightness % BRIGHTNESS_LEVELS + 1
+            }
+            Setting::Sound => settings.sound = !settings.sound,
+        }
+        settings
+    }
+
+    /// The value of the setting in `settings`, the way its editor shows it.
+    pub fn get_snapshot(self, settings: &Settings) -> Snapshot<5, 5> {
+        let canvas = Canvas::new(CellState::AnimationBlinking);
+        match self {
+            Setting::Mode => canvas.glyph(match settings.mode {
+                GameMode::Endless => 'E',
+                GameMode::Campaign => 'C',
+                GameMode::Versus => 'V',
+                GameMode::HotSeat => 'H',
+            }),
+            Setting::Players => {
+                return PlayerCountSelector::with_players(settings.players).get_snapshot()
+            }
+            Setting::Difficulty => {
+                return DifficultySelector::with_index(settings.difficulty).get_snapshot()
+            }
+            Setting::Boundary => {
+                return BoundarySelector::with_boundary_mode(settings.boundary).get_snapshot()
+            }
+            // a staircase as high as the level
+            Setting::Brightness => {
+                let level = settings.brightness.min(BRIGHTNESS_LEVELS) as usize;
+                canvas.bar_graph(&[1, 2, 3, 4, 5][..level])
+            }
+            Setting::Sound => canvas.glyph(if settings.sound { 'Y' } else { 'N' }),
+        }
+        .finish()
+    }
+}
+
+/// What picking an item that leaves the menu asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Play,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Item {
+    /// Opens another page.
+    Submenu {
+        icon: char,
+        page: &'static [Item],
+    },
+    /// Opens the editor of a setting.
+    Value(Setting),
+    Action {
+        icon: char,
+        action: Action,
+    },
+    /// Returns to the page the current one was opened from.
+    Back,
+}
+
+impl Item {
+    pub fn icon(&self) -> char {
+        match *self {
+            Item::Submenu { icon, .. } | Item::Action { icon, .. } => icon,
+            Item::Value(setting) => setting.icon(),
+            Item::Back => '<',
+        }
+    }
+}
+
+pub static OPTIONS_PAGE: [Item; 3] = [
+    Item::Value(Setting::Brightness),
+    Item::Value(Setting::Sound),
+    Item::Back,
+];
+
+pub static MAIN_PAGE: [Item; 6] = [
+    Item::Action {
+        icon: 'G',
+        action: Action::Play,
+    },
+    Item::Value(Setting::Mode),
+    Item::Value(Setting::Difficulty),
+    Item::Value(Setting::Boundary),
+    Item::Value(Setting::Players),
+    Item::Submenu {
+        icon: 'O',
+        page: &OPTIONS_PAGE,
+    },
+];
+
+#[derive(Debug, Clone, Copy)]
+struct Position {
+    page: &'static [Item],
+    cursor: usize,
+}
+
+/// Pages of items browsed with the buttons.
+///
+/// On a page A moves on to the next item and B picks it, a long A goes back to the
+/// page before. In the editor of a setting A moves on to the next value, B keeps it
+/// and a long A goes back to the page without changing the setting.
+pub struct Menu {
+    // the open pages, the current one last
+    path: Vec<Position, MENU_DEPTH>,
+    settings: Settings,
+    // the setting being edited and its new value so far
+    editing: Option<(Setting, Settings)>,
+}
+
+impl Menu {
+    /// `page` must not be empty, neither must the pages it opens.
+    pub fn new(page: &'static [Item], settings: Settings) -> Self {
+        assert!(!page.is_empty());
+        let mut path = Vec::new();
+        let _ = path.push(Position { page, cursor: 0 });
+        Menu {
+            path,
+            settings,
+            editing: None,
+        }
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// The item under the cursor.
+    pub fn item(&self) -> Item {
+        let position = self.position();
+        position.page[position.cursor]
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    /// Back to the first item of the first page, e.g. after a game.
+    pub fn home(&mut self) {
+        self.path.truncate(1);
+        self.path[0].cursor = 0;
+        self.editing = None;
+    }
+
+    /// Handles a press, returns what to do if it picked an action.
+    pub fn is_action_picked(&mut self, input: ButtonCode) -> Option<Action> {
+        if let Some((setting, draft)) = self.editing {
+            match input {
+                ButtonCode::PressedA => self.editing = Some((setting, setting.next(draft))),
+                ButtonCode::PressedB => {
+                    self.settings = draft;
+                    self.editing = None;
+                }
+                ButtonCode::LongPressedA => self.editing = None,
+                _ => (),
+            }
+            return None;
+        }
+        match input {
+            ButtonCode::PressedA => {
+                let position = self.position_mut();
+                position.cursor = (position.cursor + 1) % position.page.len();
+            }
+            ButtonCode::PressedB => match self.item() {
+                Item::Submenu { page, .. } => {
+                    // a page too deep stays closed
+                    let _ = self.path.push(Position { page, cursor: 0 });
+                }
+                Item::Value(setting) => self.editing = Some((setting, self.settings)),
+                Item::Action { action, .. } => return Some(action),
+                Item::Back => self.back(),
+            },
+            ButtonCode::LongPressedA => self.back(),
+            _ => (),
+        }
+        None
+    }
+
+    /// The icon of the item under the cursor, or the value being edited.
+    pub fn get_snapshot(&self) -> Snapshot<5, 5> {
+        match self.editing {
+            Some((setting, draft)) => setting.get_snapshot(&draft),
+            None => Canvas::new(CellState::AnimationStatic(1000))
+                .glyph(self.item().icon())
+                .finish(),
+        }
+    }
+
+    fn back(&mut self) {
+        if self.path.len() > 1 {
+            self.path.pop();
+        }
+    }
+
+    fn position(&self) -> &Position {
+        // the first page is never closed
+        self.path.last().unwrap()
+    }
+
+    fn position_mut(&mut self) -> &mut Position {
+        self.path.last_mut().unwrap()
+    }
+}
+
+impl Default for Menu {
+    fn default() -> Self {
+        Self::new(&MAIN_PAGE, Default::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(menu: &mut Menu, inputs: &[ButtonCode]) -> Option<Action> {
+        inputs
+            .iter()
+            .fold(None, |_, &input| menu.is_action_picked(input))
+    }
+
+    use ButtonCode::{LongPressedA, PressedA, PressedB};
+
+    #[test]
+    fn a_moves_on_and_wraps_around() {
+        let mut menu = Menu::default();
+        assert_eq!(menu.item().icon(), 'G');
+        press(&mut menu, &[PressedA]);
+        assert_eq!(menu.item(), Item::Value(Setting::Mode));
+        press(&mut menu, &[PressedA; 5]);
+        assert_eq!(menu.item().icon(), 'G');
+        assert_eq!(press(&mut menu, &[PressedB]), Some(Action::Play));
+    }
+
+    #[test]
+    fn submenus_go_back_by_item_and_by_long_press() {
+        let mut menu = Menu::default();
+        press(&mut menu, &[PressedA; 5]);
+        press(&mut menu, &[PressedB]);
+        assert_eq!(menu.item(), Item::Value(Setting::Brightness));
+        press(&mut menu, &[PressedA, PressedA]);
+        assert_eq!(menu.item(), Item::Back);
+        press(&mut menu, &[PressedB]);
+        assert_eq!(menu.item().icon(), 'O');
+
+        press(&mut menu, &[PressedB, PressedA, LongPressedA]);
+        assert_eq!(menu.item().icon(), 'O');
+        // the main page has nothing to go back to
+        press(&mut menu, &[LongPressedA]);
+        assert_eq!(menu.item().icon(), 'O');
+        menu.home();
+        assert_eq!(menu.item().icon(), 'G');
+    }
+
+    #[test]
+    fn edits_are_kept_on_b_only() {
+        let mut menu = Menu::default();
+        press(&mut menu, &[PressedA, PressedB, PressedA]);
+        assert!(menu.is_editing());
+        assert_eq!(menu.settings().mode, GameMode::Endless);
+        press(&mut menu, &[PressedA, PressedB]);
+        assert!(!menu.is_editing());
+        assert_eq!(menu.settings().mode, GameMode::Versus);
+
+        press(&mut menu, &[PressedB, PressedA, LongPressedA]);
+        assert_eq!(menu.settings().mode, GameMode::Versus);
+        assert_eq!(menu.item(), Item::Value(Setting::Mode));
+    }
+
+    #[test]
+    fn difficulty_brings_its_boundary() {
+        let mut settings = Settings::default();
+        for _ in 0..PROFILES.len() {
+            settings = Setting::Difficulty.next(settings);
+            assert_eq!(settings.boundary, PROFILES[settings.difficulty].boundary);
+        }
+        assert_eq!(settings, Settings::default());
+    }
+
+    #[test]
+    fn values_wrap_around() {
+        let settings = Settings::default();
+        let mut brightness = settings;
+        for _ in 0..BRIGHTNESS_LEVELS {
+            brightness = Setting::Brightness.next(brightness);
+            assert!((1..=BRIGHTNESS_LEVELS).contains(&brightness.brightness));
+        }
+        assert_eq!(brightness, settings);
+        // out of range values still draw
+        let too_bright = Settings {
+            brightness: u8::MAX,
+            ..settings
+        };
+        assert_eq!(
+            Setting::Brightness.get_snapshot(&too_bright),
+            Setting::Brightness.get_snapshot(&settings)
+        );
+        assert_eq!(Setting::Sound.next(Setting::Sound.next(settings)), settings);
+    }
+
+    #[test]
+    fn snapshot_shows_the_icon_or_the_value() {
+        let mut menu = Menu::default();
+        let icon = menu.get_snapshot();
+        assert!(icon
+            .buffer
+            .iter()
+            .flatten()
+            .all(|&cell| cell != CellState::AnimationBlinking));
+        press(&mut menu, &[PressedA, PressedA, PressedB]);
+        assert_eq!(
+            menu.get_snapshot().buffer,
+            DifficultySelector::new().get_snapshot().buffer
+        );
+    }
+}
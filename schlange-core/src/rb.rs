@@ -0,0 +1,157 @@
+pub struct RingBuffer<T, const CAP: usize> {
+    rb: [T; CAP],
+    head: usize,
+    tail: usize,
+    // head and tail meet both when empty and when full, the count tells them apart
+    len: usize,
+}
+
+#[derive(Debug)]
+pub enum RbError {
+    NoMoreSpace,
+    IsEmpty,
+}
+
+impl<T, const CAP: usize> RingBuffer<T, CAP>
+where
+    T: Default + Copy,
+{
+    pub fn new() -> Self {
+        RingBuffer {
+            rb: [Default::default(); CAP],
+            head: 0,
+            tail: 0,
+            len: 0,
+        }
+    }
+
+    pub fn put(&mut self, elem: T) -> Result<(), RbError> {
+        if self.len == CAP {
+            Err(RbError::NoMoreSpace)
+        } else {
+            self.rb[self.head] = elem;
+            self.head = (self.head + 1) % CAP;
+            self.len += 1;
+            Ok(())
+        }
+    }
+
+    pub fn get(&mut self) -> Result<T, RbError> {
+        if self.len == 0 {
+            Err(RbError::IsEmpty)
+        } else {
+            let elem = self.rb[self.tail];
+            self.tail = (self.tail + 1) % CAP;
+            self.len -= 1;
+            Ok(elem)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == CAP
+    }
+
+    pub fn capacity(&self) -> usize {
+        CAP
+    }
+
+    pub fn peek_head(&self) -> T {
+        self.rb[(self.head + CAP - 1) % CAP]
+    }
+
+    pub fn peek_tail(&self) -> T {
+        self.rb[self.tail]
+    }
+
+    pub fn iter(&self) -> RingBufferIterator<T, CAP> {
+        RingBufferIterator {
+            rb: self,
+            head: self.head,
+            remaining: self.len,
+        }
+    }
+}
+
+impl<'a, T, const CAP: usize> Iterator for RingBufferIterator<'a, T, CAP> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            None
+        } else {
+            self.remaining -= 1;
+            self.head = (self.head + CAP - 1) % CAP;
+            let elem = &self.rb.rb[self.head];
+            Some(elem)
+        }
+    }
+}
+
+pub struct RingBufferIterator<'a, T, const CAP: usize> {
+    rb: &'a RingBuffer<T, CAP>,
+    head: usize,
+    remaining: usize,
+}
+
+impl<T, const CAP: usize> Default for RingBuffer<T, CAP>
+where
+    T: Default + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fifo_order() {
+        let mut rb: RingBuffer<u8, 4> = RingBuffer::new();
+        rb.put(1).unwrap();
+        rb.put(2).unwrap();
+        assert_eq!(rb.len(), 2);
+        assert_eq!(rb.get().unwrap(), 1);
+        assert_eq!(rb.get().unwrap(), 2);
+        assert!(matches!(rb.get(), Err(RbError::IsEmpty)));
+    }
+
+    #[test]
+    fn iterates_from_head_to_tail() {
+        let mut rb: RingBuffer<u8, 4> = RingBuffer::new();
+        for i in 0..3 {
+            rb.put(i).unwrap();
+        }
+        rb.get().unwrap();
+        rb.put(3).unwrap();
+        assert_eq!(rb.peek_head(), 3);
+        assert_eq!(rb.peek_tail(), 1);
+        let items: Vec<u8> = rb.iter().copied().collect();
+        assert_eq!(items, [3, 2, 1]);
+    }
+
+    #[test]
+    fn holds_its_full_capacity() {
+        let mut rb: RingBuffer<u8, 4> = RingBuffer::new();
+        rb.put(0).unwrap();
+        rb.get().unwrap();
+        for i in 1..=4 {
+            rb.put(i).unwrap();
+        }
+        assert!(rb.is_full());
+        assert_eq!(rb.len(), 4);
+        assert!(matches!(rb.put(5), Err(RbError::NoMoreSpace)));
+        let items: Vec<u8> = rb.iter().copied().collect();
+        assert_eq!(items, [4, 3, 2, 1]);
+        assert_eq!(rb.get().unwrap(), 1);
+    }
+}
@@ -0,0 +1,494 @@
+use heapless::Vec;
+
+use crate::{
+    boundary::{BoundaryMode, CROSS_PORTALS},
+    food::FoodMode,
+    game::{Game, GameConfig, MoveResult, TailRule},
+    input::ButtonCode,
+    level::{Level, CAMPAIGN},
+};
+
+const MAGIC: [u8; 2] = *b"SR";
+const VERSION: u8 = 2;
+// magic, version, seed, difficulty, boundary, food, tail, level, ticks, result, length,
+// input count
+const HEADER_SIZE: usize = 2 + 1 + 4 + 1 + 1 + 1 + 1 + 1 + 4 + 1 + 2 + 2;
+// a tick delta as a LEB128 varint of up to 5 bytes plus the button code
+const MAX_INPUT_SIZE: usize = 5 + 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayError {
+    BufferTooSmall,
+    Truncated,
+    BadMagic,
+    UnsupportedVersion(u8),
+    UnknownButtonCode(u8),
+    UnknownBoundary(u8),
+    UnknownFoodMode(u8),
+    UnknownTailRule(u8),
+    UnknownLevel(u8),
+    UnknownMoveResult(u8),
+    /// Only the built-in portal layout can be recorded.
+    UnrecordableBoundary,
+    /// Only the levels of the built-in campaign can be recorded.
+    UnrecordableLevel,
+    TooManyInputs,
+    /// An input at or before the tick of the previous one, a tick takes one input.
+    TickOutOfOrder,
+    /// Inputs got lost while recording, the game can't be replayed.
+    Incomplete,
+    NotFinished,
+    Mismatch {
+        expected: (MoveResult, u16),
+        actual: (MoveResult, u16),
+    },
+}
+
+/// Everything needed to play a game again: how it started, the inputs at the ticks they
+/// were applied and how it ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recording<const N: usize> {
+    pub seed: u32,
+    pub difficulty: u8,
+    /// The score multiplier isn't recorded, it doesn't change how the game goes.
+    pub config: GameConfig,
+    inputs: Vec<(u32, ButtonCode), N>,
+    end: Option<(u32, MoveResult, u16)>,
+    complete: bool,
+}
+
+impl<const N: usize> Recording<N> {
+    pub const MAX_ENCODED_LEN: usize = HEADER_SIZE + N * MAX_INPUT_SIZE;
+
+    pub fn new(seed: u32, difficulty: u8, config: GameConfig) -> Self {
+        Recording {
+            seed,
+            difficulty,
+            config,
+            inputs: Vec::new(),
+            end: None,
+            complete: true,
+        }
+    }
+
+    /// Notes the input passed to `Game::step` at `tick`, counted from zero. The ticks
+    /// have to go up from one input to the next.
+    pub fn record(&mut self, tick: u32, input: ButtonCode) -> Result<(), ReplayError> {
+        if self.inputs.last().is_some_and(|&(last, _)| tick <= last) {
+            self.complete = false;
+            return Err(ReplayError::TickOutOfOrder);
+        }
+        self.inputs.push((tick, input)).map_err(|_| {
+            self.complete = false;
+            ReplayError::TooManyInputs
+        })
+    }
+
+    /// Notes that the step at `tick` ended the game with `result` and a snake of `length`.
+    pub fn finish(&mut self, tick: u32, result: MoveResult, length: usize) {
+        self.end = Some((tick, result, length as u16));
+    }
+
+    pub fn inputs(&self) -> &[(u32, ButtonCode)] {
+        &self.inputs
+    }
+
+    pub fn encode(&self, buffer: &mut [u8]) -> Result<usize, ReplayError> {
+        if !self.complete {
+            return Err(ReplayError::Incomplete);
+        }
+        let (ticks, result, length) = self.end.ok_or(ReplayError::NotFinished)?;
+        let mut writer = Writer { buffer, len: 0 };
+        writer.put(&MAGIC)?;
+        writer.put(&[VERSION])?;
+        writer.put(&self.seed.to_le_bytes())?;
+        writer.put(&[
+            self.difficulty,
+            boundary_tag(self.config.boundary)?,
+            food_mode_tag(self.config.food),
+            tail_rule_tag(self.config.tail),
+            level_tag(self.config.level)?,
+        ])?;
+        writer.put(&ticks.to_le_bytes())?;
+        writer.put(&[move_result_tag(result)])?;
+        writer.put(&length.to_le_bytes())?;
+        writer.put(&(self.inputs.len() as u16).to_le_bytes())?;
+        let mut last_tick = 0;
+        for &(tick, code) in self.inputs.iter() {
+            writer.put_varint(tick - last_tick)?;
+            writer.put(&[button_code_tag(code)])?;
+            last_tick = tick;
+        }
+        Ok(writer.len)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, ReplayError> {
+        let mut reader = Reader { bytes };
+        if reader.take::<2>()? != MAGIC {
+            return Err(ReplayError::BadMagic);
+        }
+        let [version] = reader.take()?;
+        if version != VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+        let seed = u32::from_le_bytes(reader.take()?);
+        let [difficulty, boundary, food, tail, level] = reader.take()?;
+        let ticks = u32::from_le_bytes(reader.take()?);
+        let [result] = reader.take()?;
+        let length = u16::from_le_bytes(reader.take()?);
+        let count = u16::from_le_bytes(reader.take()?);
+
+        let config = GameConfig {
+            boundary: boundary_from_tag(boundary)?,
+            food: food_mode_from_tag(food)?,
+            tail: tail_rule_from_tag(tail)?,
+            level: level_from_tag(level)?,
+            ..Default::default()
+        };
+        let mut recording = Recording::new(seed, difficulty, config);
+        recording.end = Some((ticks, move_result_from_tag(result)?, length));
+        let mut tick = 0;
+        for _ in 0..count {
+            tick = reader.take_varint()?.wrapping_add(tick);
+            let [code] = reader.take()?;
+            recording.record(tick, button_code_from_tag(code)?)?;
+        }
+        Ok(recording)
+    }
+}
+
+/// Plays `recording` on a fresh game and returns how it ended, its result and length.
+pub fn replay<const NCOLS: usize, const NROWS: usize, const NCELLS: usize, const N: usize>(
+    recording: &Recording<N>,
+) -> Result<(MoveResult, u16), ReplayError> {
+    let (ticks, _, _) = recording.end.ok_or(ReplayError::NotFinished)?;
+    let mut game: Game<NCOLS, NROWS, NCELLS> = Game::with_seed(recording.seed, recording.config);
+    let mut inputs = recording.inputs.iter().peekable();
+    let mut result = MoveResult::Trivial;
+    for tick in 0..=ticks {
+        let input = inputs
+            .next_if(|&&(at, _)| at == tick)
+            .map(|&(_, code)| code);
+        result = game.step(input);
+        if result != MoveResult::Trivial {
+            break;
+        }
+    }
+    Ok((result, game.len() as u16))
+}
+
+/// Replays `recording` and checks it ends the way it did when it was recorded.
+pub fn verify<const NCOLS: usize, const NROWS: usize, const NCELLS: usize, const N: usize>(
+    recording: &Recording<N>,
+) -> Result<(), ReplayError> {
+    let (_, result, length) = recording.end.ok_or(ReplayError::NotFinished)?;
+    let actual = replay::<NCOLS, NROWS, NCELLS, N>(recording)?;
+    if actual == (result, length) {
+        Ok(())
+    } else {
+        Err(ReplayError::Mismatch {
+            expected: (result, length),
+            actual,
+        })
+    }
+}
+
+struct Writer<'a> {
+    buffer: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> Writer<'a> {
+    fn put(&mut self, bytes: &[u8]) -> Result<(), ReplayError> {
+        let target = self
+            .buffer
+            .get_mut(self.len..self.len + bytes.len())
+            .ok_or(ReplayError::BufferTooSmall)?;
+        target.copy_from_slice(bytes);
+        self.len += bytes.len();
+        Ok(())
+    }
+
+    fn put_varint(&mut self, mut value: u32) -> Result<(), ReplayError> {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                return self.put(&[byte]);
+            }
+            self.put(&[byte | 0x80])?;
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take<const L: usize>(&mut self) -> Result<[u8; L], ReplayError> {
+        if self.bytes.len() < L {
+            return Err(ReplayError::Truncated);
+        }
+        let (head, tail) = self.bytes.split_at(L);
+        self.bytes = tail;
+        let mut taken = [0; L];
+        taken.copy_from_slice(head);
+        Ok(taken)
+    }
+
+    fn take_varint(&mut self) -> Result<u32, ReplayError> {
+        let mut value = 0u32;
+        for shift in (0..35).step_by(7) {
+            let [byte] = self.take()?;
+            value |= ((byte & 0x7f) as u32) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(ReplayError::Truncated)
+    }
+}
+
+fn boundary_tag(boundary: BoundaryMode) -> Result<u8, ReplayError> {
+    match boundary {
+        BoundaryMode::Wrap => Ok(0),
+        BoundaryMode::Walls => Ok(1),
+        BoundaryMode::Portals(portals) if portals == &CROSS_PORTALS[..] => Ok(2),
+        BoundaryMode::Portals(_) => Err(ReplayError::UnrecordableBoundary),
+    }
+}
+
+fn boundary_from_tag(tag: u8) -> Result<BoundaryMode, ReplayError> {
+    match tag {
+        0 => Ok(BoundaryMode::Wrap),
+        1 => Ok(BoundaryMode::Walls),
+        2 => Ok(BoundaryMode::Portals(&CROSS_PORTALS)),
+        _ => Err(ReplayError::UnknownBoundary(tag)),
+    }
+}
+
+// 0 for the empty board, the campaign levels from 1 on
+fn level_tag(level: Option<&'static Level>) -> Result<u8, ReplayError> {
+    match level {
+        None => Ok(0),
+        Some(level) => CAMPAIGN
+            .iter()
+            .position(|campaign_level| campaign_level == level)
+            .map(|index| index as u8 + 1)
+            .ok_or(ReplayError::UnrecordableLevel),
+    }
+}
+
+fn level_from_tag(tag: u8) -> Result<Option<&'static Level>, ReplayError> {
+    match tag {
+        0 => Ok(None),
+        _ => CAMPAIGN
+            .get(tag as usize - 1)
+            .map(Some)
+            .ok_or(ReplayError::UnknownLevel(tag)),
+    }
+}
+
+const FOOD_MODES: [FoodMode; 4] = [
+    FoodMode::Uniform,
+    FoodMode::NotAdjacent,
+    FoodMode::Reachable,
+    FoodMode::Far,
+];
+
+fn food_mode_tag(food: FoodMode) -> u8 {
+    FOOD_MODES.iter().position(|&f| f == food).unwrap() as u8
+}
+
+fn food_mode_from_tag(tag: u8) -> Result<FoodMode, ReplayError> {
+    FOOD_MODES
+        .get(tag as usize)
+        .copied()
+        .ok_or(ReplayError::UnknownFoodMode(tag))
+}
+
+const TAIL_RULES: [TailRule; 2] = [TailRule::Strict, TailRule::Chase];
+
+fn tail_rule_tag(tail: TailRule) -> u8 {
+    TAIL_RULES.iter().position(|&t| t == tail).unwrap() as u8
+}
+
+fn tail_rule_from_tag(tag: u8) -> Result<TailRule, ReplayError> {
+    TAIL_RULES
+        .get(tag as usize)
+        .copied()
+        .ok_or(ReplayError::UnknownTailRule(tag))
+}
+
+const MOVE_RESULTS: [MoveResult; 5] = [
+    MoveResult::Trivial,
+    MoveResult::BiteYourself,
+    MoveResult::HitWall,
+    MoveResult::Win,
+    MoveResult::LevelCleared,
+];
+
+fn move_result_tag(result: MoveResult) -> u8 {
+    MOVE_RESULTS.iter().position(|&r| r == result).unwrap() as u8
+}
+
+fn move_result_from_tag(tag: u8) -> Result<MoveResult, ReplayError> {
+    MOVE_RESULTS
+        .get(tag as usize)
+        .copied()
+        .ok_or(ReplayError::UnknownMoveResult(tag))
+}
+
+const BUTTON_CODES: [ButtonCode; 7] = [
+    ButtonCode::PressedA,
+    ButtonCode::PressedB,
+    ButtonCode::LongPressedA,
+    ButtonCode::LongPressedB,
+    ButtonCode::DoublePressedA,
+    ButtonCode::DoublePressedB,
+    ButtonCode::PressedAB,
+];
+
+fn button_code_tag(code: ButtonCode) -> u8 {
+    BUTTON_CODES.iter().position(|&c| c == code).unwrap() as u8
+}
+
+fn button_code_from_tag(tag: u8) -> Result<ButtonCode, ReplayError> {
+    BUTTON_CODES
+        .get(tag as usize)
+        .copied()
+        .ok_or(ReplayError::UnknownButtonCode(tag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plays a game the way the firmware does and records it.
+    fn record_game(
+        seed: u32,
+        boundary: BoundaryMode,
+        turns: &[(u32, ButtonCode)],
+    ) -> Recording<64> {
+        let config = GameConfig {
+            boundary,
+            food: FoodMode::Reachable,
+            level: Some(&CAMPAIGN[2]),
+            ..Default::default()
+        };
+        let mut recording = Recording::new(seed, 2, config);
+        let mut game: Game<5, 5, 25> = Game::with_seed(seed, config);
+        for tick in 0..1000 {
+            let input = turns
+                .iter()
+                .find(|&&(at, _)| at == tick)
+                .map(|&(_, code)| code);
+            if let Some(code) = input {
+                recording.record(tick, code).unwrap();
+            }
+            let result = game.step(input);
+            if result != MoveResult::Trivial {
+                recording.finish(tick, result, game.len());
+                return recording;
+            }
+        }
+        panic!("the game did not end");
+    }
+
+    #[test]
+    fn encoding_round_trips() {
+        let mut recording: Recording<4> = Recording::new(
+            99,
+            3,
+            GameConfig {
+                boundary: BoundaryMode::Walls,
+                food: FoodMode::Far,
+                tail: TailRule::Chase,
+                level: Some(&CAMPAIGN[1]),
+                ..Default::default()
+            },
+        );
+        recording.record(0, ButtonCode::PressedA).unwrap();
+        recording.record(300, ButtonCode::PressedAB).unwrap();
+        recording.finish(301, MoveResult::HitWall, 4);
+        let mut buffer = [0; Recording::<4>::MAX_ENCODED_LEN];
+        let len = recording.encode(&mut buffer).unwrap();
+        // a long pause between inputs takes a second byte
+        assert_eq!(len, HEADER_SIZE + 2 + 3);
+        assert_eq!(Recording::<4>::decode(&buffer[..len]).unwrap(), recording);
+    }
+
+    #[test]
+    fn recorded_game_replays_to_the_same_end() {
+        let turns = [
+            (1, ButtonCode::PressedB),
+            (3, ButtonCode::PressedB),
+            (4, ButtonCode::LongPressedA),
+            (6, ButtonCode::PressedA),
+            (9, ButtonCode::PressedB),
+        ];
+        let recording = record_game(7, BoundaryMode::Portals(&CROSS_PORTALS), &turns);
+        let mut buffer = [0; 256];
+        let len = recording.encode(&mut buffer).unwrap();
+        let decoded = Recording::<64>::decode(&buffer[..len]).unwrap();
+        verify::<5, 5, 25, 64>(&decoded).unwrap();
+    }
+
+    #[test]
+    fn tampered_recording_does_not_verify() {
+        let mut recording = record_game(7, BoundaryMode::Walls, &[]);
+        recording.end = recording
+            .end
+            .map(|(ticks, result, length)| (ticks, result, length + 1));
+        assert!(matches!(
+            verify::<5, 5, 25, 64>(&recording),
+            Err(ReplayError::Mismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_foreign_data() {
+        assert_eq!(
+            Recording::<4>::decode(b"XX").unwrap_err(),
+            ReplayError::BadMagic
+        );
+        assert_eq!(
+            Recording::<4>::decode(b"SR\x01").unwrap_err(),
+            ReplayError::UnsupportedVersion(1)
+        );
+        assert_eq!(
+            Recording::<4>::decode(b"SR\x02\x00").unwrap_err(),
+            ReplayError::Truncated
+        );
+    }
+
+    #[test]
+    fn lost_inputs_make_the_recording_unusable() {
+        let mut recording: Recording<1> = Recording::new(1, 0, GameConfig::default());
+        recording.record(0, ButtonCode::PressedA).unwrap();
+        assert_eq!(
+            recording.record(1, ButtonCode::PressedA),
+            Err(ReplayError::TooManyInputs)
+        );
+        recording.finish(2, MoveResult::BiteYourself, 1);
+        assert_eq!(recording.encode(&mut [0; 64]), Err(ReplayError::Incomplete));
+    }
+
+    #[test]
+    fn ticks_have_to_go_up() {
+        let mut recording: Recording<4> = Recording::new(1, 0, GameConfig::default());
+        recording.record(5, ButtonCode::PressedA).unwrap();
+        assert_eq!(
+            recording.record(5, ButtonCode::PressedB),
+            Err(ReplayError::TickOutOfOrder)
+        );
+        assert_eq!(
+            recording.record(3, ButtonCode::PressedB),
+            Err(ReplayError::TickOutOfOrder)
+        );
+        assert_eq!(recording.inputs(), [(5, ButtonCode::PressedA)]);
+        recording.finish(6, MoveResult::BiteYourself, 1);
+        assert_eq!(recording.encode(&mut [0; 64]), Err(ReplayError::Incomplete));
+    }
+}
@@ -0,0 +1,77 @@
+use micro_rand::Random;
+
+/// The source of all randomness in a game, so a game can be replayed from its seed.
+pub trait Rng {
+    /// A number in `0..bound`, all equally likely, `bound` is never zero.
+    fn next_below(&mut self, bound: u32) -> u32;
+}
+
+// the modulus of the Lehmer generator, its states are 1..MODULUS
+const MODULUS: u32 = 2_147_483_647;
+
+/// The default generator, the Lehmer generator of `micro_rand`.
+pub struct SeededRng {
+    rnd: Random,
+}
+
+impl SeededRng {
+    pub fn new(seed: u32) -> Self {
+        // the generator gets stuck on zero and its modulus, keep it in between
+        let seed = 1 + seed as i64 % (MODULUS as i64 - 1);
+        SeededRng {
+            rnd: Random::new(seed),
+        }
+    }
+}
+
+impl Rng for SeededRng {
+    fn next_below(&mut self, bound: u32) -> u32 {
+        // the generator only hands out its state divided by the modulus and scaling
+        // that as a float is biased, so take the state and drop the values above the
+        // last whole multiple of `bound`
+        let states = MODULUS - 1;
+        let limit = states - states % bound;
+        loop {
+            let state = (self.rnd.next_f64() * MODULUS as f64 + 0.5) as u32;
+            if state - 1 < limit {
+                return (state - 1) % bound;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let mut a = SeededRng::new(42);
+        let mut b = SeededRng::new(42);
+        for _ in 0..100 {
+            assert_eq!(a.next_below(25), b.next_below(25));
+        }
+    }
+
+    #[test]
+    fn next_below_is_uniform() {
+        let mut rng = SeededRng::new(7);
+        let mut counts = [0u32; 6];
+        for _ in 0..60_000 {
+            counts[rng.next_below(6) as usize] += 1;
+        }
+        // chi-squared with 5 degrees of freedom, 20.5 is the 0.1% critical value
+        let chi_squared: f64 = counts
+            .iter()
+            .map(|&count| (count as f64 - 10_000.0).powi(2) / 10_000.0)
+            .sum();
+        assert!(chi_squared < 20.5, "{counts:?}");
+    }
+
+    #[test]
+    fn zero_seed_does_not_get_stuck() {
+        let mut rng = SeededRng::new(0);
+        let first = rng.next_below(1000);
+        assert!((0..10).any(|_| rng.next_below(1000) != first));
+    }
+}
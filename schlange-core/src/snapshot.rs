@@ -0,0 +1,62 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snapshot<const NCOLS: usize, const NROWS: usize> {
+    pub buffer: [[CellState; NROWS]; NCOLS],
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellState {
+    #[default]
+    Empty,
+    SnakeHead,
+    SnakeTail,
+    Food,
+    Wall,
+    /// The second snake of a versus round.
+    RivalHead,
+    RivalTail,
+    AnimationStatic(u16),
+    AnimationFadingInterFrame,
+    AnimationFlareUpInterFrame,
+    AnimationBlinking,
+}
+
+impl<const NCOLS: usize, const NROWS: usize> Snapshot<NCOLS, NROWS> {
+    pub fn new() -> Self {
+        Snapshot {
+            buffer: [[CellState::Empty; NROWS]; NCOLS],
+        }
+    }
+
+    /// The same picture with every lit cell blinking, e.g. to show a paused game.
+    pub fn blinking(&self) -> Self {
+        let mut snapshot = *self;
+        for cell in snapshot.buffer.iter_mut().flatten() {
+            if *cell != CellState::Empty {
+                *cell = CellState::AnimationBlinking;
+            }
+        }
+        snapshot
+    }
+}
+
+impl<const NCOLS: usize, const NROWS: usize> Default for Snapshot<NCOLS, NROWS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blinking_keeps_empty_cells_dark() {
+        let mut snapshot: Snapshot<3, 2> = Snapshot::new();
+        snapshot.buffer[0][1] = CellState::SnakeHead;
+        snapshot.buffer[2][0] = CellState::Food;
+        let blinking = snapshot.blinking();
+        assert_eq!(blinking.buffer[0][1], CellState::AnimationBlinking);
+        assert_eq!(blinking.buffer[2][0], CellState::AnimationBlinking);
+        assert_eq!(blinking.buffer[1][1], CellState::Empty);
+    }
+}
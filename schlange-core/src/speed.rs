@@ -0,0 +1,79 @@
+/// How the speed-up is spread over the growth of the snake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ramp {
+    /// The same speed-up for every food eaten.
+    Linear,
+    /// Hardly any speed-up at first, most of it towards full length.
+    EaseIn,
+    /// Most of the speed-up early on, then it levels off.
+    EaseOut,
+}
+
+/// The delay between two ticks of a game, shrinking as the snake grows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpeedCurve {
+    /// Delay of a snake of length one.
+    pub start_ms: u64,
+    /// Delay from `full_speed_length` on.
+    pub min_ms: u64,
+    pub ramp: Ramp,
+    pub full_speed_length: usize,
+}
+
+impl SpeedCurve {
+    pub const fn new(start_ms: u64, min_ms: u64, ramp: Ramp, full_speed_length: usize) -> Self {
+        SpeedCurve {
+            start_ms,
+            min_ms,
+            ramp,
+            full_speed_length,
+        }
+    }
+
+    pub fn turn_delay_ms(&self, length: usize) -> u64 {
+        let span = self.full_speed_length.saturating_sub(1).max(1) as u64;
+        let progress = (length.saturating_sub(1) as u64).min(span);
+        // the eased share of the speed-up as a fraction of `span * span`
+        let eased = match self.ramp {
+            Ramp::Linear => progress * span,
+            Ramp::EaseIn => progress * progress,
+            Ramp::EaseOut => span * span - (span - progress) * (span - progress),
+        };
+        let speed_up = self.start_ms.saturating_sub(self.min_ms);
+        self.start_ms - speed_up * eased / (span * span)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(ramp: Ramp) -> SpeedCurve {
+        SpeedCurve {
+            start_ms: 500,
+            min_ms: 200,
+            ramp,
+            full_speed_length: 11,
+        }
+    }
+
+    #[test]
+    fn delay_goes_from_start_to_min() {
+        for ramp in [Ramp::Linear, Ramp::EaseIn, Ramp::EaseOut] {
+            let curve = curve(ramp);
+            assert_eq!(curve.turn_delay_ms(1), 500);
+            assert_eq!(curve.turn_delay_ms(11), 200);
+            assert_eq!(curve.turn_delay_ms(25), 200);
+            for length in 1..25 {
+                assert!(curve.turn_delay_ms(length + 1) <= curve.turn_delay_ms(length));
+            }
+        }
+    }
+
+    #[test]
+    fn ramps_differ_halfway() {
+        assert_eq!(curve(Ramp::Linear).turn_delay_ms(6), 350);
+        assert_eq!(curve(Ramp::EaseIn).turn_delay_ms(6), 425);
+        assert_eq!(curve(Ramp::EaseOut).turn_delay_ms(6), 275);
+    }
+}
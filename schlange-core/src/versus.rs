@@ -0,0 +1,445 @@
+use core::cmp::Ordering;
+
+use heapless::Deque;
+
+use crate::{
+    boundary::BoundaryMode,
+    food::{Board, FoodSpawner, Uniform},
+    game::{Coordinate, Direction, TailRule},
+    input::ButtonCode,
+    rb::RingBuffer,
+    rng::{Rng, SeededRng},
+    snapshot::{CellState, Snapshot},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Player {
+    /// Steers with button A.
+    One,
+    /// Steers with button B.
+    Two,
+}
+
+impl Player {
+    fn index(self) -> usize {
+        match self {
+            Player::One => 0,
+            Player::Two => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersusResult {
+    Running,
+    Won(Player),
+    /// Both snakes died on the same tick, or the board filled up with both equally long.
+    Draw,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Turn {
+    Left,
+    Right,
+}
+
+// turns a player pressed ahead of time, one is taken per tick
+const TURN_QUEUE: usize = 2;
+
+struct Snake<const NCELLS: usize> {
+    body: RingBuffer<Coordinate, NCELLS>,
+    direction: Direction,
+    turns: Deque<Turn, TURN_QUEUE>,
+    food_eaten: u32,
+}
+
+impl<const NCELLS: usize> Snake<NCELLS> {
+    fn new(head: Coordinate, direction: Direction) -> Self {
+        let mut body = RingBuffer::new();
+        body.put(head).unwrap();
+        Snake {
+            body,
+            direction,
+            turns: Deque::new(),
+            food_eaten: 0,
+        }
+    }
+}
+
+/// Two snakes on one board, each player steers one with a single button: a press turns
+/// left, holding it on turns right instead.
+///
+/// Both snakes move at the same time, so neither player gets to go first. A snake dies
+/// on the edge of a walled board, on either body and on the cell the other head moves
+/// to; when both die on the same tick the round is a draw. The food always spawns
+/// uniformly, a spawner that looks at one head would favour one player.
+pub struct Versus<const NCOLS: usize, const NROWS: usize, const NCELLS: usize, R = SeededRng> {
+    boundary: BoundaryMode,
+    tail: TailRule,
+    snakes: [Snake<NCELLS>; 2],
+    // the cells covered by either snake
+    occupied: [[bool; NROWS]; NCOLS],
+    food: Coordinate,
+    rng: R,
+}
+
+impl<const NCOLS: usize, const NROWS: usize, const NCELLS: usize> Versus<NCOLS, NROWS, NCELLS> {
+    pub fn with_seed(seed: u32, boundary: BoundaryMode, tail: TailRule) -> Self {
+        Self::new(SeededRng::new(seed), boundary, tail)
+    }
+}
+
+impl<const NCOLS: usize, const NROWS: usize, const NCELLS: usize, R: Rng>
+    Versus<NCOLS, NROWS, NCELLS, R>
+{
+    /// Player one starts on the left heading north, player two on the mirrored cell on
+    /// the right heading south.
+    pub fn new(rng: R, boundary: BoundaryMode, tail: TailRule) -> Self {
+        // coordinates are u8
+        const { assert!(NCOLS * NROWS == NCELLS && NCOLS >= 2 && NCOLS <= 256 && NROWS <= 256) };
+        let one = Coordinate {
+            row: (NROWS / 2) as u8,
+            col: (NCOLS / 4) as u8,
+        };
+        let two = Coordinate {
+            row: (NROWS - 1 - NROWS / 2) as u8,
+            col: (NCOLS - 1 - NCOLS / 4) as u8,
+        };
+        let mut versus = Versus {
+            boundary,
+            tail,
+            snakes: [
+                Snake::new(one, Direction::North),
+                Snake::new(two, Direction::South),
+            ],
+            occupied: [[false; NROWS]; NCOLS],
+            food: Default::default(),
+            rng,
+        };
+        versus.occupy(one, true);
+        versus.occupy(two, true);
+        versus.give_food();
+        versus
+    }
+
+    /// Queues the turn a button press stands for. Both buttons at once are a press of
+    /// each player.
+    pub fn steer(&mut self, input: ButtonCode) {
+        match input {
+            // the second press of a double press is a turn of its own
+            ButtonCode::PressedA | ButtonCode::DoublePressedA => self.turn(Player::One),
+            ButtonCode::LongPressedA => self.turn_right(Player::One),
+            ButtonCode::PressedB | ButtonCode::DoublePressedB => self.turn(Player::Two),
+            ButtonCode::LongPressedB => self.turn_right(Player::Two),
+            ButtonCode::PressedAB => {
+                self.turn(Player::One);
+                self.turn(Player::Two);
+            }
+        }
+    }
+
+    /// Takes a queued turn of each player and moves both snakes by one cell.
+    pub fn step(&mut self) -> VersusResult {
+        for snake in self.snakes.iter_mut() {
+            match snake.turns.pop_front() {
+                Some(Turn::Left) => snake.direction = snake.direction.left(),
+                Some(Turn::Right) => snake.direction = snake.direction.right(),
+                None => (),
+            }
+        }
+        let moves = [0, 1].map(|index| {
+            let snake = &self.snakes[index];
+            self.boundary
+                .advance::<NCOLS, NROWS>(snake.body.peek_head(), snake.direction)
+        });
+        let eating = moves.map(|next| next.is_some_and(|(cell, _)| cell == self.food));
+        let dead = [0, 1].map(|index| {
+            let Some((cell, _)) = moves[index] else {
+                return true;
+            };
+            let (mine, theirs) = (&self.snakes[index], &self.snakes[1 - index]);
+            let other = moves[1 - index].map(|(cell, _)| cell);
+            let head_on = other == Some(cell);
+            // even where a tail makes way, two heads can't pass through each other
+            let swapped = cell == theirs.body.peek_head() && other == Some(mine.body.peek_head());
+            head_on || swapped || (self.is_snake(cell) && !self.is_vacated(cell, &eating))
+        });
+        match dead {
+            [true, true] => return VersusResult::Draw,
+            [true, false] => return VersusResult::Won(Player::Two),
+            [false, true] => return VersusResult::Won(Player::One),
+            [false, false] => (),
+        }
+
+        // both tails go before either head comes, a head may follow the other tail
+        for (index, eating) in eating.into_iter().enumerate() {
+            if !eating {
+                let tail = self.snakes[index].body.get().unwrap();
+                self.occupy(tail, false);
+            }
+        }
+        for (index, next) in moves.into_iter().enumerate() {
+            let (cell, direction) = next.unwrap();
+            let snake = &mut self.snakes[index];
+            snake.direction = direction;
+            // neither snake outgrows the board, the buffer can't run out of room
+            snake.body.put(cell).unwrap();
+            self.occupy(cell, true);
+        }
+        if let Some(index) = eating.iter().position(|&eating| eating) {
+            self.snakes[index].food_eaten += 1;
+            if self.is_board_full() {
+                return match self.len(Player::One).cmp(&self.len(Player::Two)) {
+                    Ordering::Greater => VersusResult::Won(Player::One),
+                    Ordering::Less => VersusResult::Won(Player::Two),
+                    Ordering::Equal => VersusResult::Draw,
+                };
+            }
+            self.give_food();
+        }
+        VersusResult::Running
+    }
+
+    pub fn head(&self, player: Player) -> Coordinate {
+        self.snakes[player.index()].body.peek_head()
+    }
+
+    pub fn len(&self, player: Player) -> usize {
+        self.snakes[player.index()].body.len()
+    }
+
+    pub fn direction(&self, player: Player) -> Direction {
+        self.snakes[player.index()].direction
+    }
+
+    pub fn food_eaten(&self, player: Player) -> u32 {
+        self.snakes[player.index()].food_eaten
+    }
+
+    pub fn food(&self) -> Coordinate {
+        self.food
+    }
+
+    /// Player one is drawn like the snake of a single game, player two as the rival.
+    pub fn get_snapshot(&self) -> Snapshot<NCOLS, NROWS> {
+        let mut snapshot = Snapshot::new();
+        let looks = [
+            (CellState::SnakeHead, CellState::SnakeTail),
+            (CellState::RivalHead, CellState::RivalTail),
+        ];
+        for (snake, (head_look, tail_look)) in self.snakes.iter().zip(looks) {
+            let mut cells = snake.body.iter();
+            if let Some(head) = cells.next() {
+                snapshot.buffer[head.col as usize][head.row as usize] = head_look;
+            }
+            for tail in cells {
+                snapshot.buffer[tail.col as usize][tail.row as usize] = tail_look;
+            }
+        }
+        if !self.is_snake(self.food) {
+            snapshot.buffer[self.food.col as usize][self.food.row as usize] = CellState::Food;
+        }
+        snapshot
+    }
+
+    fn is_snake(&self, coordinate: Coordinate) -> bool {
+        self.occupied[coordinate.col as usize][coordinate.row as usize]
+    }
+
+    /// Whether a tail leaves `coordinate` before the heads move, `eating` tells which
+    /// snakes grow this tick and keep their tails.
+    fn is_vacated(&self, coordinate: Coordinate, eating: &[bool; 2]) -> bool {
+        self.tail == TailRule::Chase
+            && self
+                .snakes
+                .iter()
+                .zip(eating)
+                .any(|(snake, &eating)| !eating && snake.body.peek_tail() == coordinate)
+    }
+
+    fn is_board_full(&self) -> bool {
+        self.occupied.iter().flatten().all(|&occupied| occupied)
+    }
+
+    fn give_food(&mut self) {
+        let board = Board {
+            occupied: &self.occupied,
+            head: self.snakes[0].body.peek_head(),
+            boundary: self.boundary,
+        };
+        if let Some(food) = Uniform.spawn(&board, &mut self.rng) {
+            self.food = food;
+        }
+    }
+
+    fn turn(&mut self, player: Player) {
+        // a player mashing the button ahead loses the surplus, not the next tick
+        let _ = self.snakes[player.index()].turns.push_back(Turn::Left);
+    }
+
+    /// A long press comes after the press it started with, which turned left already.
+    fn turn_right(&mut self, player: Player) {
+        let turns = &mut self.snakes[player.index()].turns;
+        if turns.back() == Some(&Turn::Left) {
+            turns.pop_back();
+            let _ = turns.push_back(Turn::Right);
+        } else if turns.capacity() - turns.len() >= 2 {
+            // the snake took the left turn, two right ones leave it heading right of
+            // where it was going before
+            let _ = turns.push_back(Turn::Right);
+            let _ = turns.push_back(Turn::Right);
+        }
+        // without room for both the long press is dropped, one right turn alone would
+        // only take back the left one
+    }
+
+    fn occupy(&mut self, coordinate: Coordinate, occupied: bool) {
+        self.occupied[coordinate.col as usize][coordinate.row as usize] = occupied;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::Button;
+
+    type Versus = super::Versus<5, 5, 25>;
+
+    fn versus(boundary: BoundaryMode) -> Versus {
+        Versus::with_seed(1, boundary, TailRule::Strict)
+    }
+
+    fn run(versus: &mut Versus, ticks: usize) -> VersusResult {
+        for _ in 0..ticks {
+            let result = versus.step();
+            if result != VersusResult::Running {
+                return result;
+            }
+        }
+        VersusResult::Running
+    }
+
+    #[test]
+    fn starts_are_mirrored() {
+        let versus = versus(BoundaryMode::Wrap);
+        assert_eq!(versus.head(Player::One), Coordinate { row: 2, col: 1 });
+        assert_eq!(versus.head(Player::Two), Coordinate { row: 2, col: 3 });
+        assert_eq!(versus.direction(Player::One), Direction::North);
+        assert_eq!(versus.direction(Player::Two), Direction::South);
+        let snapshot = versus.get_snapshot();
+        assert_eq!(snapshot.buffer[1][2], CellState::SnakeHead);
+        assert_eq!(snapshot.buffer[3][2], CellState::RivalHead);
+    }
+
+    /// What the buttons report for a long press of `button`.
+    fn hold(versus: &mut Versus, button: Button) {
+        let (press, long_press) = match button {
+            Button::A => (ButtonCode::PressedA, ButtonCode::LongPressedA),
+            Button::B => (ButtonCode::PressedB, ButtonCode::LongPressedB),
+        };
+        versus.steer(press);
+        versus.steer(long_press);
+    }
+
+    #[test]
+    fn each_player_steers_with_one_button() {
+        let mut versus = versus(BoundaryMode::Wrap);
+        versus.steer(ButtonCode::PressedA);
+        hold(&mut versus, Button::B);
+        versus.step();
+        assert_eq!(versus.direction(Player::One), Direction::West);
+        assert_eq!(versus.direction(Player::Two), Direction::West);
+
+        // one queued turn per tick
+        versus.steer(ButtonCode::PressedA);
+        versus.steer(ButtonCode::DoublePressedA);
+        versus.step();
+        assert_eq!(versus.direction(Player::One), Direction::South);
+        versus.step();
+        assert_eq!(versus.direction(Player::One), Direction::Ost);
+        assert_eq!(versus.direction(Player::Two), Direction::West);
+    }
+
+    #[test]
+    fn long_press_after_the_tick_still_turns_right() {
+        let mut versus = versus(BoundaryMode::Wrap);
+        versus.steer(ButtonCode::PressedA);
+        versus.step();
+        assert_eq!(versus.direction(Player::One), Direction::West);
+        versus.steer(ButtonCode::LongPressedA);
+        run(&mut versus, 2);
+        assert_eq!(versus.direction(Player::One), Direction::Ost);
+    }
+
+    #[test]
+    fn long_press_turns_right_twice_or_not_at_all() {
+        let mut versus = versus(BoundaryMode::Wrap);
+        versus.steer(ButtonCode::PressedA);
+        versus.step();
+        // a turn pressed earlier still waits, there is room for one more
+        let _ = versus.snakes[0].turns.push_back(Turn::Right);
+        versus.steer(ButtonCode::LongPressedA);
+        assert_eq!(versus.snakes[0].turns.len(), 1);
+        versus.step();
+        assert_eq!(versus.direction(Player::One), Direction::North);
+        assert_eq!(run(&mut versus, 1), VersusResult::Running);
+        assert_eq!(versus.direction(Player::One), Direction::North);
+    }
+
+    #[test]
+    fn chord_turns_both_snakes() {
+        let mut versus = versus(BoundaryMode::Wrap);
+        versus.steer(ButtonCode::PressedAB);
+        versus.step();
+        assert_eq!(versus.direction(Player::One), Direction::West);
+        assert_eq!(versus.direction(Player::Two), Direction::Ost);
+    }
+
+    #[test]
+    fn head_on_is_a_draw() {
+        // both turn towards each other and meet in the middle column
+        let mut versus = versus(BoundaryMode::Walls);
+        hold(&mut versus, Button::A);
+        hold(&mut versus, Button::B);
+        assert_eq!(versus.step(), VersusResult::Draw);
+    }
+
+    #[test]
+    fn swapping_heads_is_a_draw() {
+        // on four columns the players start side by side
+        for tail in [TailRule::Strict, TailRule::Chase] {
+            let mut versus: super::Versus<4, 5, 20> =
+                super::Versus::with_seed(1, BoundaryMode::Walls, tail);
+            versus.steer(ButtonCode::PressedA);
+            versus.steer(ButtonCode::LongPressedA);
+            versus.steer(ButtonCode::PressedB);
+            versus.steer(ButtonCode::LongPressedB);
+            assert_eq!(versus.step(), VersusResult::Draw);
+        }
+    }
+
+    #[test]
+    fn running_into_the_other_body_loses() {
+        for (tail, result) in [
+            (TailRule::Strict, VersusResult::Won(Player::Two)),
+            (TailRule::Chase, VersusResult::Running),
+        ] {
+            let mut versus = Versus::with_seed(1, BoundaryMode::Wrap, tail);
+            // player two grows by one, player one turns east towards its tail
+            versus.food = Coordinate { row: 3, col: 3 };
+            hold(&mut versus, Button::A);
+            assert_eq!(versus.step(), VersusResult::Running);
+            assert_eq!(versus.len(Player::Two), 2);
+            assert_eq!(versus.food_eaten(Player::Two), 1);
+            assert_eq!(versus.step(), result);
+        }
+    }
+
+    #[test]
+    fn edge_of_a_walled_board_loses() {
+        let mut versus = versus(BoundaryMode::Walls);
+        versus.steer(ButtonCode::PressedA);
+        assert_eq!(run(&mut versus, 5), VersusResult::Won(Player::Two));
+        assert_eq!(versus.head(Player::One), Coordinate { row: 2, col: 0 });
+    }
+}
@@ -1,4 +1,5 @@
-use crate::led::{self, send_snapshot, CellState, Snapshot};
+use crate::led::send_snapshot;
+use schlange_core::snapshot::{CellState, Snapshot};
 
 struct AnimationFrame {
     snapshot: Snapshot<5, 5>,
@@ -18,14 +19,27 @@ where
     Sender: Fn(&Snapshot<5, 5>),
 {
     pub async fn playback(&self) {
-        for frame in self.seq.iter() {
-            (self.cb)(&frame.snapshot);
-            embassy_time::Timer::after_millis(frame.delay).await;
-        }
+        playback(
+            self.seq.iter().map(|frame| (frame.snapshot, frame.delay)),
+            &self.cb,
+        )
+        .await;
     }
 }
 
-type IntroType = Animation<for<'a> fn(&'a led::Snapshot<5, 5>), 4>;
+/// Sends every snapshot and holds it for its delay in milliseconds, generated frame
+/// sequences such as marquees go through here as well.
+pub async fn playback<Sender>(frames: impl Iterator<Item = (Snapshot<5, 5>, u64)>, cb: Sender)
+where
+    Sender: Fn(&Snapshot<5, 5>),
+{
+    for (snapshot, delay) in frames {
+        cb(&snapshot);
+        embassy_time::Timer::after_millis(delay).await;
+    }
+}
+
+type IntroType = Animation<for<'a> fn(&'a Snapshot<5, 5>), 4>;
 pub static INTRO: IntroType = Animation {
     seq: [
         AnimationFrame {
@@ -200,7 +214,7 @@ pub static INTRO: IntroType = Animation {
     cb: send_snapshot,
 };
 
-type DefeatType = Animation<for<'a> fn(&'a led::Snapshot<5, 5>), 7>;
+type DefeatType = Animation<for<'a> fn(&'a Snapshot<5, 5>), 7>;
 pub static DEFEAT: DefeatType = Animation {
     seq: [
         AnimationFrame {
@@ -501,7 +515,7 @@ pub static DEFEAT: DefeatType = Animation {
     cb: send_snapshot,
 };
 
-type VictoryType = Animation<for<'a> fn(&'a led::Snapshot<5, 5>), 7>;
+type VictoryType = Animation<for<'a> fn(&'a Snapshot<5, 5>), 7>;
 pub static VICTORY: VictoryType = Animation {
     seq: [
         AnimationFrame {
@@ -801,3 +815,395 @@ pub static VICTORY: VictoryType = Animation {
     ],
     cb: send_snapshot,
 };
+
+type CrashType = Animation<for<'a> fn(&'a Snapshot<5, 5>), 6>;
+pub static CRASH: CrashType = Animation {
+    seq: [
+        AnimationFrame {
+            snapshot: Snapshot {
+                buffer: [
+                    [
+                        CellState::AnimationFlareUpInterFrame,
+                        CellState::AnimationFlareUpInterFrame,
+                        CellState::AnimationFlareUpInterFrame,
+                        CellState::AnimationFlareUpInterFrame,
+                        CellState::AnimationFlareUpInterFrame,
+                    ],
+                    [
+                        CellState::AnimationFlareUpInterFrame,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::AnimationFlareUpInterFrame,
+                    ],
+                    [
+                        CellState::AnimationFlareUpInterFrame,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::AnimationFlareUpInterFrame,
+                    ],
+                    [
+                        CellState::AnimationFlareUpInterFrame,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::AnimationFlareUpInterFrame,
+                    ],
+                    [
+                        CellState::AnimationFlareUpInterFrame,
+                        CellState::AnimationFlareUpInterFrame,
+                        CellState::AnimationFlareUpInterFrame,
+                        CellState::AnimationFlareUpInterFrame,
+                        CellState::AnimationFlareUpInterFrame,
+                    ],
+                ],
+            },
+            delay: 250,
+        },
+        AnimationFrame {
+            snapshot: Snapshot {
+                buffer: [
+                    [
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                    ],
+                    [
+                        CellState::AnimationStatic(1000),
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::AnimationStatic(1000),
+                    ],
+                    [
+                        CellState::AnimationStatic(1000),
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::AnimationStatic(1000),
+                    ],
+                    [
+                        CellState::AnimationStatic(1000),
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::AnimationStatic(1000),
+                    ],
+                    [
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                    ],
+                ],
+            },
+            delay: 250,
+        },
+        AnimationFrame {
+            snapshot: Snapshot {
+                buffer: [
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                    [
+                        CellState::Empty,
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                        CellState::Empty,
+                    ],
+                    [
+                        CellState::Empty,
+                        CellState::AnimationStatic(1000),
+                        CellState::Empty,
+                        CellState::AnimationStatic(1000),
+                        CellState::Empty,
+                    ],
+                    [
+                        CellState::Empty,
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                        CellState::Empty,
+                    ],
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                ],
+            },
+            delay: 250,
+        },
+        AnimationFrame {
+            snapshot: Snapshot {
+                buffer: [
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::AnimationStatic(1000),
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                ],
+            },
+            delay: 250,
+        },
+        AnimationFrame {
+            snapshot: Snapshot {
+                buffer: [
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::AnimationFadingInterFrame,
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                ],
+            },
+            delay: 500,
+        },
+        AnimationFrame {
+            snapshot: Snapshot {
+                buffer: [
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                ],
+            },
+            delay: 100,
+        },
+    ],
+    cb: send_snapshot,
+};
+
+type CountdownType = Animation<for<'a> fn(&'a Snapshot<5, 5>), 3>;
+pub static COUNTDOWN: CountdownType = Animation {
+    seq: [
+        AnimationFrame {
+            snapshot: Snapshot {
+                buffer: [
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                    [
+                        CellState::AnimationStatic(1000),
+                        CellState::Empty,
+                        CellState::AnimationStatic(1000),
+                        CellState::Empty,
+                        CellState::AnimationStatic(1000),
+                    ],
+                    [
+                        CellState::AnimationStatic(1000),
+                        CellState::Empty,
+                        CellState::AnimationStatic(1000),
+                        CellState::Empty,
+                        CellState::AnimationStatic(1000),
+                    ],
+                    [
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                    ],
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                ],
+            },
+            delay: 500,
+        },
+        AnimationFrame {
+            snapshot: Snapshot {
+                buffer: [
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                    [
+                        CellState::AnimationStatic(1000),
+                        CellState::Empty,
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                    ],
+                    [
+                        CellState::AnimationStatic(1000),
+                        CellState::Empty,
+                        CellState::AnimationStatic(1000),
+                        CellState::Empty,
+                        CellState::AnimationStatic(1000),
+                    ],
+                    [
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                        CellState::Empty,
+                        CellState::AnimationStatic(1000),
+                    ],
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                ],
+            },
+            delay: 500,
+        },
+        AnimationFrame {
+            snapshot: Snapshot {
+                buffer: [
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                    [
+                        CellState::Empty,
+                        CellState::AnimationStatic(1000),
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::AnimationStatic(1000),
+                    ],
+                    [
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                        CellState::AnimationStatic(1000),
+                    ],
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::AnimationStatic(1000),
+                    ],
+                    [
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                        CellState::Empty,
+                    ],
+                ],
+            },
+            delay: 500,
+        },
+    ],
+    cb: send_snapshot,
+};
@@ -1,6 +1,8 @@
+use embassy_futures::select::{select, Either};
 use embassy_nrf::gpio::{AnyPin, Input, Level, Pull};
-use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, signal::Signal};
-use embassy_time::{Duration, Timer};
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, channel::Channel};
+use embassy_time::{Duration, Instant, Timer};
+use schlange_core::input::{Button, ButtonCode, Edge, GestureClassifier, GestureWindows};
 
 struct Debouncer<'a> {
     input: Input<'a>,
@@ -12,40 +14,78 @@ impl<'a> Debouncer<'a> {
         Self { input, debounce }
     }
 
-    async fn debounce(&mut self) -> Level {
+    /// Waits for the next stable level change and returns it together with the
+    /// moment of the edge that started it.
+    async fn debounce(&mut self) -> (Level, Instant) {
         loop {
             let l1 = self.input.get_level();
 
             self.input.wait_for_any_edge().await;
+            let at = Instant::now();
 
             Timer::after(self.debounce).await;
 
             let l2 = self.input.get_level();
             if l1 != l2 {
-                break l2;
+                break (l2, at);
             }
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-pub enum ButtonCode {
-    PressedA,
-    PressedB,
-}
+// a few presses between two ticks are kept, so quick turns at high speed are not lost
+const BUTTON_QUEUE_DEPTH: usize = 4;
+
+pub static BUTTON_CHANNEL: Channel<CriticalSectionRawMutex, ButtonCode, BUTTON_QUEUE_DEPTH> =
+    Channel::new();
 
-pub static BUTTON_SIGNAL: Signal<CriticalSectionRawMutex, ButtonCode> = Signal::new();
+pub async fn wait_for_code() -> ButtonCode {
+    BUTTON_CHANNEL.receive().await
+}
 
-pub fn try_get_code() -> Option<ButtonCode> {
-    BUTTON_SIGNAL.try_take()
+pub fn drain_codes() {
+    BUTTON_CHANNEL.clear();
 }
 
+static EDGE_CHANNEL: Channel<CriticalSectionRawMutex, (Button, Edge, u64), 8> = Channel::new();
+
 #[embassy_executor::task(pool_size = 2)]
-pub async fn btn_task(btn: AnyPin, btn_signal: ButtonCode) {
+pub async fn btn_task(btn: AnyPin, button: Button) {
     let mut btn = Debouncer::new(Input::new(btn, Pull::None), Duration::from_millis(20));
     loop {
-        if btn.debounce().await == Level::Low {
-            BUTTON_SIGNAL.signal(btn_signal);
+        let (level, at) = btn.debounce().await;
+        let edge = match level {
+            Level::Low => Edge::Pressed,
+            Level::High => Edge::Released,
+        };
+        EDGE_CHANNEL.send((button, edge, at.as_millis())).await;
+    }
+}
+
+#[embassy_executor::task]
+pub async fn gesture_task(windows: GestureWindows) {
+    let mut classifier = GestureClassifier::new(windows);
+    loop {
+        let edge = match classifier.next_deadline() {
+            Some(deadline) => {
+                match select(
+                    EDGE_CHANNEL.receive(),
+                    Timer::at(Instant::from_millis(deadline)),
+                )
+                .await
+                {
+                    Either::First(edge) => Some(edge),
+                    Either::Second(_) => None,
+                }
+            }
+            None => Some(EDGE_CHANNEL.receive().await),
+        };
+        if let Some((button, edge, at)) = edge {
+            classifier.feed(button, edge, at);
+        }
+        while let Some(code) = classifier.poll(Instant::now().as_millis()) {
+            // drop the press rather than stall the classifier if nobody is listening
+            let _ = BUTTON_CHANNEL.try_send(code);
         }
     }
 }
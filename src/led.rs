@@ -1,12 +1,29 @@
+use core::sync::atomic::{AtomicU8, Ordering};
+
 use embassy_nrf::gpio::{Level, Output, OutputDrive};
 use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, signal::Signal};
 use embassy_time::Timer;
+use schlange_core::menu::BRIGHTNESS_LEVELS;
+use schlange_core::snapshot::{CellState, Snapshot};
 
 use crate::LedPins;
 
 // go for the signal because we can, 50-bytes transaction per 100-500 ms is not of a big deal
 pub static SNAPSHOT_SIGNAL: Signal<CriticalSectionRawMutex, Snapshot<5, 5>> = Signal::new();
 
+// the brightness setting of the menu, every pixel is scaled by it
+static BRIGHTNESS: AtomicU8 = AtomicU8::new(BRIGHTNESS_LEVELS);
+
+pub fn set_brightness(level: u8) {
+    BRIGHTNESS.store(level.clamp(1, BRIGHTNESS_LEVELS), Ordering::Relaxed);
+}
+
+/// How many of the 1000 us of a pixel it is lit at `brightness`.
+fn on_time_micros(brightness: i16) -> u64 {
+    brightness.clamp(0, 1000) as u64 * BRIGHTNESS.load(Ordering::Relaxed) as u64
+        / BRIGHTNESS_LEVELS as u64
+}
+
 struct LedMatrix<'a, const NCOLS: usize, const NROWS: usize> {
     cols: [Output<'a>; NCOLS],
     rows: [Output<'a>; NROWS],
@@ -33,12 +50,14 @@ impl<'a> LedMatrix<'a, 5, 5> {
             frame: Frame::new(),
         }
     }
+}
 
-    fn set_frame(&mut self, frame: Frame<5, 5>) {
+impl<'a, const NCOLS: usize, const NROWS: usize> LedMatrix<'a, NCOLS, NROWS> {
+    fn set_frame(&mut self, frame: Frame<NCOLS, NROWS>) {
         self.frame = frame;
     }
 
-    fn get_frame(&self) -> &Frame<5, 5> {
+    fn get_frame(&self) -> &Frame<NCOLS, NROWS> {
         &self.frame
     }
 
@@ -51,38 +70,42 @@ impl<'a> LedMatrix<'a, 5, 5> {
                         Timer::after_micros(1000).await;
                     }
                     PixelState::Solid(l) => {
+                        let on = on_time_micros(l as i16);
                         row_led.set_high();
-                        Timer::after_micros(l as u64).await;
+                        Timer::after_micros(on).await;
                         row_led.set_low();
-                        Timer::after_micros(1000 - l as u64).await;
+                        Timer::after_micros(1000 - on).await;
                     }
                     PixelState::Blinking(mut state) => {
-                        if state.brightness > 0 {
+                        let on = on_time_micros(state.brightness);
+                        if on > 0 {
                             row_led.set_high();
-                            Timer::after_micros(state.brightness as u64).await;
+                            Timer::after_micros(on).await;
                             row_led.set_low();
                         }
-                        Timer::after_micros(1000 - state.brightness as u64).await;
+                        Timer::after_micros(1000 - on).await;
                         state.process();
                         *frame_row = PixelState::Blinking(state);
                     }
                     PixelState::Fading(mut state) => {
-                        if state.brightness > 0 {
+                        let on = on_time_micros(state.brightness);
+                        if on > 0 {
                             row_led.set_high();
-                            Timer::after_micros(state.brightness as u64).await;
+                            Timer::after_micros(on).await;
                             row_led.set_low();
                         }
-                        Timer::after_micros(1000 - state.brightness as u64).await;
+                        Timer::after_micros(1000 - on).await;
                         state.process();
                         *frame_row = PixelState::Fading(state);
                     }
                     PixelState::FlareUp(mut state) => {
-                        if state.brightness > 0 {
+                        let on = on_time_micros(state.brightness);
+                        if on > 0 {
                             row_led.set_high();
-                            Timer::after_micros(state.brightness as u64).await;
+                            Timer::after_micros(on).await;
                             row_led.set_low();
                         }
-                        Timer::after_micros(1000 - state.brightness as u64).await;
+                        Timer::after_micros(1000 - on).await;
                         state.process();
                         *frame_row = PixelState::FlareUp(state);
                     }
@@ -181,39 +204,13 @@ impl<const MIN: i16, const MAX: i16, const STEP: i16> BlinkingPixel<MIN, MAX, ST
 
 #[derive(Debug, Clone, Copy)]
 struct Frame<const NCOLS: usize, const NROWS: usize> {
-    pub buffer: [[PixelState; NCOLS]; NROWS],
+    pub buffer: [[PixelState; NROWS]; NCOLS],
 }
 
-impl Frame<5, 5> {
+impl<const NCOLS: usize, const NROWS: usize> Frame<NCOLS, NROWS> {
     pub fn new() -> Self {
         Frame {
-            buffer: Default::default(),
-        }
-    }
-}
-
-#[derive(Debug, Clone, Copy)]
-pub struct Snapshot<const NCOLS: usize, const NROWS: usize> {
-    pub buffer: [[CellState; NCOLS]; NROWS],
-}
-
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
-pub enum CellState {
-    #[default]
-    Empty,
-    SnakeHead,
-    SnakeTail,
-    Food,
-    AnimationStatic(u16),
-    AnimationFadingInterFrame,
-    AnimationFlareUpInterFrame,
-    AnimationBlinking,
-}
-
-impl Snapshot<5, 5> {
-    pub fn new() -> Self {
-        Snapshot {
-            buffer: Default::default(),
+            buffer: [[PixelState::Off; NROWS]; NCOLS],
         }
     }
 }
@@ -223,17 +220,21 @@ pub fn send_snapshot(snapshot: &Snapshot<5, 5>) {
 }
 
 #[derive(Debug)]
-struct Render {
-    prev_snapshot: Snapshot<5, 5>,
+struct Render<const NCOLS: usize, const NROWS: usize> {
+    prev_snapshot: Snapshot<NCOLS, NROWS>,
 }
 
-impl Render {
+impl<const NCOLS: usize, const NROWS: usize> Render<NCOLS, NROWS> {
     fn new() -> Self {
         Render {
             prev_snapshot: Snapshot::new(),
         }
     }
-    fn render(&mut self, snapshot: Snapshot<5, 5>, current_frame: &Frame<5, 5>) -> Frame<5, 5> {
+    fn render(
+        &mut self,
+        snapshot: Snapshot<NCOLS, NROWS>,
+        current_frame: &Frame<NCOLS, NROWS>,
+    ) -> Frame<NCOLS, NROWS> {
         let mut frame = Frame::new();
         for (col, frame_cols) in frame.buffer.iter_mut().enumerate() {
             for (row, frame_pixel) in frame_cols.iter_mut().enumerate() {
@@ -241,6 +242,11 @@ impl Render {
                     CellState::Empty => PixelState::Off,
                     CellState::SnakeHead => PixelState::Solid(1000),
                     CellState::SnakeTail => PixelState::Solid(100),
+                    // dimmer than the first snake, yet brighter than a wall
+                    CellState::RivalHead => PixelState::Solid(300),
+                    CellState::RivalTail => PixelState::Solid(40),
+                    // dimmer than the tail so it can't be taken for the snake
+                    CellState::Wall => PixelState::Solid(20),
                     CellState::Food => {
                         if self.prev_snapshot.buffer[col][row] == CellState::Food {
                             current_frame.buffer[col][row]
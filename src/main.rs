@@ -3,28 +3,41 @@
 
 mod animation;
 mod buttons;
-mod difficulty;
 mod fmt;
 mod led;
-mod rb;
+mod sound;
 
 use crate::buttons::btn_task;
-use crate::buttons::ButtonCode;
+use crate::buttons::gesture_task;
 use crate::led::led_task;
+use crate::sound::sound_task;
 
+use animation::playback;
+use animation::COUNTDOWN;
+use animation::CRASH;
 use animation::DEFEAT;
 use animation::INTRO;
 use animation::VICTORY;
-use buttons::try_get_code;
-use difficulty::DifficultySelector;
-use embassy_time::Timer;
-use fmt::unwrap;
-use heapless::FnvIndexSet;
-use led::send_snapshot;
-use led::CellState;
-use led::Snapshot;
-use micro_rand::Random;
-use rb::RingBuffer;
+use buttons::drain_codes;
+use buttons::wait_for_code;
+use core::fmt::Write;
+use core::ops::Range;
+use embassy_futures::select::{select, Either};
+use embassy_nrf::nvmc::Nvmc;
+use embassy_time::{Instant, Timer};
+use fmt::{info, unwrap, Bytes};
+use heapless::String;
+use led::{send_snapshot, set_brightness};
+use schlange_core::app::{App, State, Transition, REPLAY_INPUTS};
+use schlange_core::clock::Clock;
+use schlange_core::game::{Game, MoveResult};
+use schlange_core::highscore::{score_index, HighScoreStore};
+use schlange_core::input::{Button, GestureWindows};
+use schlange_core::marquee::Marquee;
+use schlange_core::menu::{GameMode, Settings};
+use schlange_core::replay::Recording;
+use schlange_core::versus::{Player, VersusResult};
+use sound::{beep, set_sound, CRASH_BEEP, EAT_BEEP};
 #[cfg(feature = "defmt")]
 use {defmt_rtt as _, panic_probe as _};
 
@@ -52,209 +65,195 @@ assign_resources! {
     btn_b_pin: ButtonBPin {
         btn_pin: P0_23,
     }
+    flash: Flash {
+        nvmc: NVMC,
+    }
+    speaker: Speaker {
+        pwm: PWM0,
+        pin: P0_00,
+    }
     // add more resources to more structs if needed, for example defining one struct for each task
 }
-enum Direction {
-    North,
-    Ost,
-    South,
-    West,
-}
 
-#[derive(Default, Clone, Copy, PartialEq, Eq, Hash)]
-struct Coordinate {
-    row: u8,
-    col: u8,
-}
+// the two pages at the end of the flash that memory.x keeps away from the program
+const HIGHSCORE_FLASH: Range<u32> = 0x7e000..0x80000;
 
-struct Game {
-    snake: RingBuffer<Coordinate, 25>,
-    no_snake: FnvIndexSet<Coordinate, 32>,
-    direction: Direction,
-    food: Coordinate,
-    rnd: Random,
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    let mut config: Config = Default::default();
+    config.hfclk_source = HfclkSource::ExternalXtal;
+    let p = embassy_nrf::init(config);
+    let r = split_resources!(p);
+    unwrap!(spawner.spawn(led_task(r.led_pins)));
+    unwrap!(spawner.spawn(btn_task(r.btn_a_pin.btn_pin.into(), Button::A)));
+    unwrap!(spawner.spawn(btn_task(r.btn_b_pin.btn_pin.into(), Button::B)));
+    unwrap!(spawner.spawn(gesture_task(GestureWindows::default())));
+    unwrap!(spawner.spawn(sound_task(r.speaker)));
+    let mut highscores = unwrap!(HighScoreStore::new(
+        Nvmc::new(r.flash.nvmc),
+        HIGHSCORE_FLASH
+    ));
+    let mut app = App::new(EmbassyClock, Settings::default());
+    let mut shown = None;
+    loop {
+        // a press is handled as soon as it comes, not at the next timer
+        let input = match app.next_deadline_ms() {
+            Some(deadline) => {
+                match select(wait_for_code(), Timer::at(Instant::from_millis(deadline))).await {
+                    Either::First(code) => Some(code),
+                    Either::Second(()) => None,
+                }
+            }
+            None => Some(wait_for_code().await),
+        };
+        let eaten = food_eaten(&app);
+        let transition = app.poll(input);
+        if food_eaten(&app) > eaten {
+            beep(EAT_BEEP);
+        }
+        if let Some(transition) = transition {
+            enter(&mut app, transition, &mut highscores).await;
+            set_brightness(app.settings().brightness);
+            set_sound(app.settings().sound);
+            // whatever was played on the way in covered the last snapshot
+            shown = None;
+        }
+        // the led task starts blinking pixels over with every snapshot, only send changes
+        let snapshot = app.get_snapshot();
+        if shown != Some(snapshot) {
+            send_snapshot(&snapshot);
+            shown = Some(snapshot);
+        }
+    }
 }
 
-#[derive(Debug)]
-enum SnakeError {
-    Fatal,
-}
+struct EmbassyClock;
 
-enum MoveResult {
-    Trivial,
-    BiteYourself,
-    Win,
+impl Clock for EmbassyClock {
+    fn now_ms(&self) -> u64 {
+        Instant::now().as_millis()
+    }
 }
 
-impl Game {
-    fn new() -> Self {
-        let snake: RingBuffer<Coordinate, 25> = RingBuffer::new();
-        let mut no_snake: FnvIndexSet<Coordinate, 32> = FnvIndexSet::new();
-        for row in 0..5 {
-            for col in 0..5 {
-                let _ = no_snake.insert(Coordinate { row, col });
+/// Plays what goes with `transition`, the state machine waits for it.
+async fn enter(
+    app: &mut App<EmbassyClock>,
+    transition: Transition,
+    highscores: &mut HighScoreStore<Nvmc<'_>>,
+) {
+    let mode = app.settings().mode;
+    if transition.from == State::Playing
+        && transition.to != State::Paused
+        && mode != GameMode::Versus
+    {
+        log_recording(app.recording());
+    }
+    match transition.to {
+        State::Menu | State::Turn(_) => {
+            // drain the presses queued up during the end animation
+            drain_codes();
+        }
+        State::Intro => {
+            if transition.from != State::Playing {
+                info!("difficulty {}", app.profile().name);
             }
+            INTRO.playback().await;
         }
-        let rnd = Random::new(embassy_time::Instant::now().as_ticks() as i64);
-        let mut game = Game {
-            direction: Direction::North,
-            snake,
-            no_snake,
-            rnd,
-            food: Default::default(),
-        };
-
-        game.snake_add_head(Coordinate { row: 2, col: 2 }).unwrap();
-        game.give_food();
-        game
-    }
-
-    fn is_snake(&self, coordinate: Coordinate) -> bool {
-        !self.no_snake.contains(&coordinate)
-    }
-
-    fn give_food(&mut self) {
-        let empty_count = self.no_snake.len() as i32;
-        if empty_count > 0 {
-            let random = self.rnd.next_int_i32(0, empty_count - 1);
-            self.food = *self.no_snake.iter().nth(random as usize).unwrap();
+        State::Playing if transition.from == State::Paused => {
+            COUNTDOWN.playback().await;
+            drain_codes();
         }
-    }
-
-    fn update_direction(&mut self, input: ButtonCode) {
-        match input {
-            ButtonCode::PressedA => {
-                self.direction = match self.direction {
-                    Direction::North => Direction::West,
-                    Direction::Ost => Direction::North,
-                    Direction::South => Direction::Ost,
-                    Direction::West => Direction::South,
-                }
+        State::Playing => {
+            // only a campaign has a level number to show first
+            if transition.from != State::Intro {
+                info!("difficulty {}", app.profile().name);
+                INTRO.playback().await;
+                drain_codes();
             }
-            ButtonCode::PressedB => {
-                self.direction = match self.direction {
-                    Direction::North => Direction::Ost,
-                    Direction::Ost => Direction::South,
-                    Direction::South => Direction::West,
-                    Direction::West => Direction::North,
-                }
+            // enough to replay the level on the host together with the inputs
+            let seed = app.recording().seed;
+            match mode {
+                GameMode::Versus => info!("versus"),
+                GameMode::HotSeat => info!(
+                    "player {} with seed {}",
+                    app.hot_seat().current_player().unwrap_or(0),
+                    seed
+                ),
+                GameMode::Campaign => info!("level {} with seed {}", app.level_index(), seed),
+                GameMode::Endless => info!("endless with seed {}", seed),
             }
         }
-    }
-
-    fn get_new_head_coordinate(&self) -> Coordinate {
-        let head = self.snake.peek_head();
-        match self.direction {
-            Direction::Ost => Coordinate {
-                row: head.row,
-                col: (head.col + 1) % 5,
-            },
-            Direction::South => Coordinate {
-                col: head.col,
-                row: (head.row + 1) % 5,
-            },
-            Direction::West => Coordinate {
-                row: head.row,
-                col: (head.col + 5 - 1) % 5,
-            },
-            Direction::North => Coordinate {
-                col: head.col,
-                row: (head.row + 5 - 1) % 5,
-            },
-        }
-    }
-
-    fn is_food(&self, coordinate: Coordinate) -> bool {
-        self.food == coordinate
-    }
-
-    fn do_move(&mut self) -> Result<MoveResult, SnakeError> {
-        let new_head = self.get_new_head_coordinate();
-        if self.is_snake(new_head) {
-            Ok(MoveResult::BiteYourself)
-        } else {
-            let result = self.snake_add_head(new_head);
-            if self.is_food(new_head) {
-                self.give_food();
-            } else {
-                self.snake_cut_tail()?;
+        State::GameOver(result) => {
+            if matches!(result, MoveResult::BiteYourself | MoveResult::HitWall) {
+                beep(CRASH_BEEP);
+            }
+            match result {
+                MoveResult::BiteYourself => DEFEAT.playback().await,
+                MoveResult::HitWall => CRASH.playback().await,
+                _ => VICTORY.playback().await,
             }
-            result
+            // a hot-seat game is ranked at the end, not against the high scores
+            let new_best = score_index(mode, app.settings().difficulty).is_some_and(|index| {
+                // losing a high score is no reason to stop playing
+                highscores.submit(index, app.score()).unwrap_or(false)
+            });
+            show_score(app.score(), new_best).await;
         }
-    }
-
-    fn get_snapshot(&mut self) -> Snapshot<5, 5> {
-        let mut snapshot = Snapshot::new();
-
-        let mut snake_iter = self.snake.iter();
-        let head = snake_iter.next().unwrap();
-        snapshot.buffer[head.col as usize][head.row as usize] = CellState::SnakeHead;
-        for tail in snake_iter {
-            snapshot.buffer[tail.col as usize][tail.row as usize] = CellState::SnakeTail;
+        State::VersusOver(result) => {
+            beep(CRASH_BEEP);
+            send_snapshot(&app.get_snapshot());
+            Timer::after_millis(1000).await;
+            let text = match result {
+                VersusResult::Won(Player::One) => "P1 WINS",
+                VersusResult::Won(Player::Two) => "P2 WINS",
+                _ => "DRAW",
+            };
+            info!("versus: {}", text);
+            scroll(text).await;
         }
-        snapshot.buffer[self.food.col as usize][self.food.row as usize] = CellState::Food;
-
-        snapshot
-    }
-
-    fn snake_add_head(&mut self, coordinate: Coordinate) -> Result<MoveResult, SnakeError> {
-        self.no_snake.remove(&coordinate);
-        match self.snake.put(coordinate) {
-            Ok(_) => Ok(MoveResult::Trivial),
-            Err(_) => Ok(MoveResult::Win),
+        State::Ranking => {
+            // four entries of at most "4.P4 " and ten digits each
+            let mut text: String<64> = String::new();
+            let _ = app.hot_seat().write_ranking(&mut text);
+            info!("ranking {}", text.as_str());
+            scroll(&text).await;
         }
+        State::Settings | State::Demo | State::Paused => (),
     }
+}
+
+/// Food eaten in the running game, it only goes up while the game lasts.
+fn food_eaten(app: &App<EmbassyClock>) -> u32 {
+    let versus = app.versus().map_or(0, |versus| {
+        versus.food_eaten(Player::One) + versus.food_eaten(Player::Two)
+    });
+    app.game().map_or(0, Game::food_eaten) + versus
+}
 
-    fn snake_cut_tail(&mut self) -> Result<MoveResult, SnakeError> {
-        let tail = self.snake.get().map_err(|_| SnakeError::Fatal)?;
-        let _ = self.no_snake.insert(tail);
-        Ok(MoveResult::Trivial)
+/// Logs the encoded game so it can be decoded and replayed on the host.
+fn log_recording(recording: &Recording<REPLAY_INPUTS>) {
+    let mut buffer = [0; Recording::<REPLAY_INPUTS>::MAX_ENCODED_LEN];
+    match recording.encode(&mut buffer) {
+        Ok(len) => info!("replay {}", Bytes(&buffer[..len])),
+        Err(_) => info!("game can't be replayed"),
     }
 }
 
-#[embassy_executor::main]
-async fn main(spawner: Spawner) {
-    let mut config: Config = Default::default();
-    config.hfclk_source = HfclkSource::ExternalXtal;
-    let p = embassy_nrf::init(config);
-    let r = split_resources!(p);
-    unwrap!(spawner.spawn(led_task(r.led_pins)));
-    unwrap!(spawner.spawn(btn_task(r.btn_a_pin.btn_pin.into(), ButtonCode::PressedA)));
-    unwrap!(spawner.spawn(btn_task(r.btn_b_pin.btn_pin.into(), ButtonCode::PressedB)));
-    loop {
-        // drain the unwanted signal
-        let _ = try_get_code();
-        let mut difficulty_selector = DifficultySelector::new();
-        send_snapshot(&difficulty_selector.get_snapshot());
-        loop {
-            if let Some(btn_signal) = try_get_code() {
-                if let Some(_) = difficulty_selector.is_choice_made(btn_signal) {
-                    break;
-                }
-                send_snapshot(&difficulty_selector.get_snapshot());
-            }
-            Timer::after_millis(100).await;
-        }
-        INTRO.playback().await;
-        let mut game = Game::new();
-        loop {
-            if let Some(btn_signal) = try_get_code() {
-                game.update_direction(btn_signal);
-            }
-            let res = game.do_move().unwrap();
-            match res {
-                MoveResult::BiteYourself => {
-                    DEFEAT.playback().await;
-                    break;
-                }
-                MoveResult::Win => {
-                    VICTORY.playback().await;
-                    break;
-                }
-                _ => (),
-            }
-            send_snapshot(&game.get_snapshot());
-            Timer::after_millis(difficulty_selector.get_turn_delay_ms()).await;
-        }
+/// Scrolls the score across the display, any button skips the rest of it.
+async fn show_score(score: u32, new_best: bool) {
+    let mut text: String<13> = String::new();
+    if new_best {
+        let _ = text.push_str("HI ");
     }
+    // a u32 has at most 10 digits
+    let _ = write!(text, "{}", score);
+    scroll(&text).await;
+}
+
+/// Scrolls `text` across the display once, any button skips the rest of it.
+async fn scroll(text: &str) {
+    drain_codes();
+    let marquee = Marquee::new(text).with_column_delay_ms(150);
+    select(playback(marquee.frames(), send_snapshot), wait_for_code()).await;
+    drain_codes();
 }
@@ -0,0 +1,48 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use embassy_nrf::pwm::{Prescaler, SimplePwm};
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, signal::Signal};
+use embassy_time::Timer;
+
+use crate::Speaker;
+
+/// Notes as frequency in Hz and length in ms.
+pub type Beep = &'static [(u32, u64)];
+
+pub const EAT_BEEP: Beep = &[(1760, 40)];
+pub const CRASH_BEEP: Beep = &[(440, 120), (220, 240)];
+
+// a new beep cuts the one playing short
+static BEEP_SIGNAL: Signal<CriticalSectionRawMutex, Beep> = Signal::new();
+
+// the sound setting of the menu
+static SOUND: AtomicBool = AtomicBool::new(true);
+
+pub fn set_sound(enabled: bool) {
+    SOUND.store(enabled, Ordering::Relaxed);
+}
+
+pub fn beep(beep: Beep) {
+    if SOUND.load(Ordering::Relaxed) {
+        BEEP_SIGNAL.signal(beep);
+    }
+}
+
+#[embassy_executor::task]
+pub async fn sound_task(speaker: Speaker) {
+    let mut pwm = SimplePwm::new_1ch(speaker.pwm, speaker.pin);
+    // 1 MHz, so the period of any audible note fits the 15 bit counter
+    pwm.set_prescaler(Prescaler::Div16);
+    pwm.disable();
+    loop {
+        let beep = BEEP_SIGNAL.wait().await;
+        pwm.enable();
+        for &(frequency, ms) in beep {
+            pwm.set_period(frequency);
+            // a square wave
+            pwm.set_duty(0, pwm.max_duty() / 2);
+            Timer::after_millis(ms).await;
+        }
+        pwm.disable();
+    }
+}